@@ -0,0 +1,14 @@
+/// Judgement windows in seconds, on both sides of the note.
+pub const RANGE_PERFECT: f64 = 0.022;
+pub const RANGE_GREAT: f64 = 0.045;
+pub const RANGE_GOOD: f64 = 0.09;
+pub const RANGE_BAD: f64 = 0.135;
+
+/// The accuracy weight of a Perfect judgement, the other judgements are relative to it.
+pub const WEIGHT_PERFECT: u32 = 300;
+pub const WEIGHT_GREAT: u32 = 200;
+pub const WEIGHT_GOOD: u32 = 100;
+pub const WEIGHT_BAD: u32 = 50;
+
+/// The score of a play with only Perfect judgements.
+pub const MAX_SCORE: u32 = 1_000_000;
@@ -0,0 +1,4 @@
+pub mod constant;
+pub mod mania;
+
+pub use mania::*;
@@ -0,0 +1,374 @@
+use rhythm_core::{Judge, JudgeWindows, Note, Release, Rhythm, SimpleNote};
+use serde::{Deserialize, Serialize};
+
+use crate::constant::{
+    MAX_SCORE, RANGE_BAD, RANGE_GOOD, RANGE_GREAT, RANGE_PERFECT, WEIGHT_BAD, WEIGHT_GOOD,
+    WEIGHT_GREAT, WEIGHT_PERFECT,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum KeyEvent {
+    /// A key of the lane is pressed.
+    Press(u16),
+    /// A key of the lane is released.
+    Release(u16),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Judgement {
+    Perfect,
+    Great,
+    Good,
+    Bad,
+    Miss,
+}
+
+impl Judgement {
+    /// The judgement windows of the notes' heads and tails.
+    pub fn windows() -> JudgeWindows<Judgement> {
+        JudgeWindows::new()
+            .symmetric(Judgement::Perfect, RANGE_PERFECT)
+            .symmetric(Judgement::Great, RANGE_GREAT)
+            .symmetric(Judgement::Good, RANGE_GOOD)
+            .symmetric(Judgement::Bad, RANGE_BAD)
+    }
+
+    /// The accuracy weight of the judgement.
+    pub fn weight(&self) -> u32 {
+        match self {
+            Judgement::Perfect => WEIGHT_PERFECT,
+            Judgement::Great => WEIGHT_GREAT,
+            Judgement::Good => WEIGHT_GOOD,
+            Judgement::Bad => WEIGHT_BAD,
+            Judgement::Miss => 0,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct GameSource {
+    /// The number of lanes, e.g. 4 for 4K and 7 for 7K.
+    pub keys: u16,
+    /// Tap notes and hold notes (see [`SimpleNote::new_hold`]), whose variant is the lane index.
+    /// Times are in seconds.
+    pub notes: Vec<SimpleNote>,
+}
+
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct InputState {
+    /// The current time played in the music, in seconds.
+    pub time: f64,
+    /// Key events that happened since the last frame, in order.
+    pub events: Vec<KeyEvent>,
+}
+
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct OutputState {
+    /// If the game is finished. (All notes are passed)
+    pub finished: bool,
+    /// The current score of the player.
+    pub score: u32,
+    /// The accuracy of the judged notes so far, from 0 to 1.
+    pub accuracy: f64,
+    /// The current combo of the player.
+    pub current_combo: u32,
+    /// The maximum combo of the player.
+    pub max_combo: u32,
+    /// The judgements made in this frame, with their lanes.
+    pub judgements: Vec<(u16, Judgement)>,
+    /// The lanes of the hold notes that are being held.
+    pub holding: Vec<u16>,
+}
+
+#[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct Final {
+    pub score: u32,
+    pub accuracy: f64,
+    pub max_combo: u32,
+    pub perfects: u32,
+    pub greats: u32,
+    pub goods: u32,
+    pub bads: u32,
+    pub misses: u32,
+}
+
+pub trait ManiaEngine {
+    fn new(src: GameSource) -> Self;
+    fn forward(&mut self, input: InputState) -> OutputState;
+    fn finalize(&self) -> Final;
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DefaultManiaEngine {
+    rhythm: Rhythm<SimpleNote>,
+    keys: u16,
+    windows: JudgeWindows<Judgement>,
+
+    /// The number of judgements in the chart: one per tap note, two per hold note.
+    total_judgements: u32,
+    /// The sum of the weights of the judgements so far.
+    weights: u32,
+    current_combo: u32,
+    max_combo: u32,
+
+    current_time: f64,
+
+    judgements: Vec<Judgement>,
+}
+
+impl DefaultManiaEngine {
+    fn judge(&mut self, lane: u16, judgement: Judgement, judged: &mut Vec<(u16, Judgement)>) {
+        if judgement == Judgement::Miss {
+            self.current_combo = 0;
+        } else {
+            self.current_combo += 1;
+            self.max_combo = self.max_combo.max(self.current_combo);
+        }
+        self.weights += judgement.weight();
+        self.judgements.push(judgement);
+        judged.push((lane, judgement));
+    }
+
+    fn score(&self) -> u32 {
+        (MAX_SCORE as u64 * self.weights as u64
+            / (WEIGHT_PERFECT as u64 * self.total_judgements.max(1) as u64)) as u32
+    }
+
+    fn accuracy(&self) -> f64 {
+        if self.judgements.is_empty() {
+            return 1.0;
+        }
+        self.weights as f64 / (WEIGHT_PERFECT as f64 * self.judgements.len() as f64)
+    }
+}
+
+impl ManiaEngine for DefaultManiaEngine {
+    fn new(src: GameSource) -> Self {
+        let windows = Judgement::windows();
+        let total_judgements = src
+            .notes
+            .iter()
+            .map(|note| if note.is_hold() { 2 } else { 1 })
+            .sum();
+
+        // tap notes are widened to their judgement windows, hold notes use the hold window
+        let notes = src
+            .notes
+            .into_iter()
+            .map(|mut note| {
+                if !note.is_hold() {
+                    note.start -= windows.early();
+                    note.duration = windows.early() + windows.late();
+                }
+                note
+            })
+            .collect::<Vec<_>>();
+        let rhythm = Rhythm::new(notes).hold_window(windows.early().max(windows.late()));
+
+        DefaultManiaEngine {
+            rhythm,
+            keys: src.keys,
+            windows,
+            total_judgements,
+            weights: 0,
+            current_combo: 0,
+            max_combo: 0,
+            current_time: 0.0,
+            judgements: vec![],
+        }
+    }
+
+    fn forward(&mut self, input: InputState) -> OutputState {
+        let time_diff = input.time - self.current_time;
+        self.current_time = input.time;
+        let passed = self.rhythm.forward(time_diff);
+
+        let mut judged = vec![];
+
+        // missed notes, and hold notes held through their tails
+        for note in passed.iter() {
+            if !note.is_hold() {
+                self.judge(note.variant, Judgement::Miss, &mut judged);
+            } else if note.volume() > 1 {
+                self.judge(note.variant, Judgement::Miss, &mut judged);
+                self.judge(note.variant, Judgement::Miss, &mut judged);
+            } else {
+                self.judge(note.variant, Judgement::Perfect, &mut judged);
+            }
+        }
+
+        for event in input.events {
+            match event {
+                KeyEvent::Press(lane) if lane < self.keys => {
+                    let offset = match self.rhythm.hit(lane) {
+                        Some((note, delta)) if note.is_hold() => delta,
+                        Some((_, delta)) => delta - self.windows.early(),
+                        None => continue,
+                    };
+                    let judgement = self.windows.judge(offset).unwrap_or(Judgement::Miss);
+                    self.judge(lane, judgement, &mut judged);
+                }
+                KeyEvent::Release(lane) if lane < self.keys => {
+                    let judgement = match self.rhythm.release(lane) {
+                        Some(Release::Tail(_, offset)) => {
+                            self.windows.judge(offset).unwrap_or(Judgement::Miss)
+                        }
+                        Some(Release::Early(_, _)) => Judgement::Miss,
+                        None => continue,
+                    };
+                    self.judge(lane, judgement, &mut judged);
+                }
+                _ => {}
+            }
+        }
+
+        let holding = self
+            .rhythm
+            .availables_until(input.time)
+            .filter(|note| note.is_hold() && note.volume() == 1)
+            .map(|note| note.variant)
+            .collect();
+
+        OutputState {
+            finished: self.rhythm.finished(),
+            score: self.score(),
+            accuracy: self.accuracy(),
+            current_combo: self.current_combo,
+            max_combo: self.max_combo,
+            judgements: judged,
+            holding,
+        }
+    }
+
+    fn finalize(&self) -> Final {
+        let count = |judgement: Judgement| {
+            self.judgements.iter().filter(|&&j| j == judgement).count() as u32
+        };
+
+        Final {
+            score: self.score(),
+            accuracy: self.accuracy(),
+            max_combo: self.max_combo,
+            perfects: count(Judgement::Perfect),
+            greats: count(Judgement::Great),
+            goods: count(Judgement::Good),
+            bads: count(Judgement::Bad),
+            misses: count(Judgement::Miss),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tap(time: f64, lane: u16) -> SimpleNote {
+        SimpleNote::new(time, 0.0, 1u16, lane)
+    }
+
+    fn play(engine: &mut DefaultManiaEngine, time: f64, events: Vec<KeyEvent>) -> OutputState {
+        engine.forward(InputState { time, events })
+    }
+
+    #[test]
+    fn test_perfect_play() {
+        let mut engine = DefaultManiaEngine::new(GameSource {
+            keys: 4,
+            notes: vec![
+                tap(1.0, 0),
+                tap(1.0, 3),
+                SimpleNote::new_hold(1.5, 1.0, 1u16),
+                tap(2.0, 2),
+            ],
+        });
+
+        let output = play(
+            &mut engine,
+            1.0,
+            vec![KeyEvent::Press(0), KeyEvent::Press(3)],
+        );
+        assert_eq!(
+            output.judgements,
+            vec![(0, Judgement::Perfect), (3, Judgement::Perfect)]
+        );
+
+        let output = play(&mut engine, 1.51, vec![KeyEvent::Press(1)]);
+        assert_eq!(output.judgements, vec![(1, Judgement::Perfect)]);
+        assert_eq!(output.holding, vec![1]);
+
+        let output = play(&mut engine, 2.0, vec![KeyEvent::Press(2)]);
+        assert_eq!(output.judgements, vec![(2, Judgement::Perfect)]);
+
+        let output = play(&mut engine, 2.49, vec![KeyEvent::Release(1)]);
+        assert_eq!(output.judgements, vec![(1, Judgement::Perfect)]);
+        assert!(output.holding.is_empty());
+
+        let output = play(&mut engine, 3.0, vec![]);
+        assert!(output.finished);
+        assert_eq!(output.score, MAX_SCORE);
+        assert_eq!(output.accuracy, 1.0);
+
+        let result = engine.finalize();
+        assert_eq!(result.perfects, 5);
+        assert_eq!(result.max_combo, 5);
+    }
+
+    #[test]
+    fn test_misses_and_releases() {
+        let mut engine = DefaultManiaEngine::new(GameSource {
+            keys: 7,
+            notes: vec![
+                tap(1.0, 6),
+                SimpleNote::new_hold(2.0, 1.0, 0u16),
+                SimpleNote::new_hold(4.0, 1.0, 3u16),
+                SimpleNote::new_hold(6.0, 1.0, 5u16),
+                tap(8.0, 2),
+            ],
+        });
+
+        // out of range lanes and ghost taps are ignored
+        let output = play(
+            &mut engine,
+            0.5,
+            vec![KeyEvent::Press(7), KeyEvent::Press(6)],
+        );
+        assert!(output.judgements.is_empty());
+
+        let output = play(&mut engine, 1.06, vec![KeyEvent::Press(6)]);
+        assert_eq!(output.judgements, vec![(6, Judgement::Good)]);
+
+        // released early
+        play(&mut engine, 2.03, vec![KeyEvent::Press(0)]);
+        let output = play(&mut engine, 2.5, vec![KeyEvent::Release(0)]);
+        assert_eq!(output.judgements, vec![(0, Judgement::Miss)]);
+        assert_eq!(output.current_combo, 0);
+
+        // held through the tail
+        play(&mut engine, 4.0, vec![KeyEvent::Press(3)]);
+        let output = play(&mut engine, 5.5, vec![]);
+        assert_eq!(output.judgements, vec![(3, Judgement::Perfect)]);
+
+        // missed hold head
+        let output = play(&mut engine, 6.5, vec![]);
+        assert_eq!(
+            output.judgements,
+            vec![(5, Judgement::Miss), (5, Judgement::Miss)]
+        );
+
+        let output = play(&mut engine, 9.0, vec![]);
+        assert_eq!(output.judgements, vec![(2, Judgement::Miss)]);
+        assert!(output.finished);
+
+        let result = engine.finalize();
+        assert_eq!(
+            (result.perfects, result.greats, result.goods, result.misses),
+            (2, 1, 1, 4)
+        );
+        assert_eq!(result.max_combo, 2);
+        assert_eq!(
+            result.accuracy,
+            (300 + 200 + 300 + 100) as f64 / (300 * 8) as f64
+        );
+        assert_eq!(result.score, 375_000);
+    }
+}
@@ -0,0 +1,188 @@
+//! Benchmarks `Rhythm` on large synthetic charts, against `Linear`, the full scan it replaced.
+//! The cost of one second of play should stay flat as the chart grows.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rhythm_core::{Note, Rhythm, SimpleNote};
+
+const TPS: f64 = 400.0;
+const SIZES: [usize; 3] = [1_500, 15_000, 150_000];
+
+/// Generates a chart of 16th notes at 180 BPM (in seconds), with a one-second roll every 64 notes.
+fn chart(size: usize) -> Vec<SimpleNote> {
+    let step = 60.0 / 180.0 / 4.0;
+    let mut seed = 0x2545_f491_u32;
+    (0..size)
+        .map(|i| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let start = i as f64 * step;
+            if i % 64 == 63 {
+                SimpleNote::new(start, 1.0, u16::MAX, 3u16)
+            } else {
+                SimpleNote::new(start - 0.1, 0.2, 1u16, (seed % 2) as u16 + 1)
+            }
+        })
+        .collect()
+}
+
+/// A rhythm positioned in the middle of the chart.
+fn midway(notes: &[SimpleNote]) -> Rhythm<SimpleNote> {
+    let mut rhythm = Rhythm::new(notes.to_vec());
+    rhythm.set_time(notes[notes.len() / 2].start);
+    rhythm
+}
+
+/// The `Rhythm` before the index: every frame and hit scans all the notes that are left.
+struct Linear {
+    notes: Vec<SimpleNote>,
+    time: f64,
+    availables: Vec<SimpleNote>,
+}
+
+impl Linear {
+    fn midway(notes: &[SimpleNote]) -> Self {
+        let mut linear = Self {
+            notes: notes.to_vec(),
+            time: 0.0,
+            availables: vec![],
+        };
+        linear.set_time(notes[notes.len() / 2].start);
+        linear
+    }
+
+    fn forward(&mut self, time: f64) -> Vec<SimpleNote> {
+        self.time += time;
+        self.update_availables()
+    }
+
+    fn set_time(&mut self, time: f64) {
+        self.time = time;
+        self.availables.clone_from(&self.notes);
+        self.availables.sort_unstable();
+        self.update_availables();
+    }
+
+    fn hit(&mut self, variant: u16) -> Option<(&mut SimpleNote, f64)> {
+        let time = self.time;
+        let note = self.availables.iter_mut().find(|note| {
+            note.start() <= time
+                && note.start() + note.duration() >= time
+                && note.matches_variant(variant)
+                && note.volume() > 0
+        })?;
+        note.set_volume(note.volume() - 1);
+        let delta = time - note.start();
+        Some((note, delta))
+    }
+
+    fn update_availables(&mut self) -> Vec<SimpleNote> {
+        let mut removed = vec![];
+        self.availables.retain(|note| {
+            let keep = note.start() + note.duration() >= self.time && note.volume() > 0;
+            if !keep && note.volume() > 0 {
+                removed.push(*note);
+            }
+            keep
+        });
+        removed
+    }
+}
+
+fn forward(c: &mut Criterion) {
+    let mut group = c.benchmark_group("forward_one_second");
+    for size in SIZES {
+        let notes = chart(size);
+        group.bench_with_input(BenchmarkId::new("indexed", size), &notes, |b, notes| {
+            b.iter_batched(
+                || midway(notes),
+                |mut rhythm| {
+                    for _ in 0..TPS as usize {
+                        black_box(rhythm.forward(1.0 / TPS));
+                    }
+                    rhythm
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("linear", size), &notes, |b, notes| {
+            b.iter_batched(
+                || Linear::midway(notes),
+                |mut linear| {
+                    for _ in 0..TPS as usize {
+                        black_box(linear.forward(1.0 / TPS));
+                    }
+                    linear
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn hit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hit_one_second");
+    for size in SIZES {
+        let notes = chart(size);
+        group.bench_with_input(BenchmarkId::new("indexed", size), &notes, |b, notes| {
+            b.iter_batched(
+                || midway(notes),
+                |mut rhythm| {
+                    for i in 0..TPS as usize {
+                        black_box(rhythm.forward(1.0 / TPS));
+                        black_box(rhythm.hit((i % 2) as u16 + 1).is_some());
+                    }
+                    rhythm
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("linear", size), &notes, |b, notes| {
+            b.iter_batched(
+                || Linear::midway(notes),
+                |mut linear| {
+                    for i in 0..TPS as usize {
+                        black_box(linear.forward(1.0 / TPS));
+                        black_box(linear.hit((i % 2) as u16 + 1).is_some());
+                    }
+                    linear
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn set_time(c: &mut Criterion) {
+    let mut group = c.benchmark_group("set_time");
+    for size in SIZES {
+        let notes = chart(size);
+        let target = notes[notes.len() / 3].start;
+        group.bench_with_input(BenchmarkId::new("indexed", size), &notes, |b, notes| {
+            b.iter_batched(
+                || midway(notes),
+                |mut rhythm| {
+                    rhythm.set_time(black_box(target));
+                    rhythm
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("linear", size), &notes, |b, notes| {
+            b.iter_batched(
+                || Linear::midway(notes),
+                |mut linear| {
+                    linear.set_time(black_box(target));
+                    linear
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, forward, hit, set_time);
+criterion_main!(benches);
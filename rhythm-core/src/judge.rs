@@ -0,0 +1,121 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// The `Judge` trait maps the offset of a hit to a user-defined grade.
+pub trait Judge {
+    type Grade;
+
+    /// Returns the grade of a hit with the given offset from the note, which is negative when the hit is early.
+    /// Returns `None` if the offset is outside of every window.
+    fn judge(&self, offset: f64) -> Option<Self::Grade>;
+}
+
+/// A judgement window. Hits with an offset in `(-early, late)` get the grade.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JudgeWindow<G> {
+    pub grade: G,
+    pub early: f64,
+    pub late: f64,
+}
+
+/// A table of judgement windows, checked in order, so the strictest window should come first.
+///
+/// ```
+/// use rhythm_core::{Judge, JudgeWindows};
+///
+/// let windows = JudgeWindows::new()
+///     .window("perfect", 0.02, 0.03)
+///     .window("good", 0.05, 0.08);
+/// assert_eq!(windows.judge(-0.01), Some("perfect"));
+/// assert_eq!(windows.judge(-0.03), Some("good"));
+/// assert_eq!(windows.judge(0.1), None);
+/// assert_eq!((windows.early(), windows.late()), (0.05, 0.08));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JudgeWindows<G> {
+    pub windows: Vec<JudgeWindow<G>>,
+}
+
+impl<G> Default for JudgeWindows<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G> JudgeWindows<G> {
+    pub fn new() -> Self {
+        Self { windows: vec![] }
+    }
+
+    /// Adds a window that accepts hits up to `early` before and `late` after the note.
+    pub fn window(mut self, grade: G, early: f64, late: f64) -> Self {
+        self.windows.push(JudgeWindow { grade, early, late });
+        self
+    }
+
+    /// Adds a window that accepts hits up to `range` before and after the note.
+    pub fn symmetric(self, grade: G, range: f64) -> Self {
+        self.window(grade, range, range)
+    }
+
+    /// Returns how early a hit can be to get any grade.
+    pub fn early(&self) -> f64 {
+        self.windows
+            .iter()
+            .map(|window| window.early)
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns how late a hit can be to get any grade.
+    pub fn late(&self) -> f64 {
+        self.windows
+            .iter()
+            .map(|window| window.late)
+            .fold(0.0, f64::max)
+    }
+}
+
+impl<G: Clone> Judge for JudgeWindows<G> {
+    type Grade = G;
+
+    fn judge(&self, offset: f64) -> Option<G> {
+        self.windows
+            .iter()
+            .find(|window| -window.early < offset && offset < window.late)
+            .map(|window| window.grade.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Grade {
+        Perfect,
+        Great,
+        Bad,
+    }
+
+    #[test]
+    fn test_asymmetric_windows() {
+        let windows = JudgeWindows::new()
+            .symmetric(Grade::Perfect, 0.016)
+            .window(Grade::Great, 0.04, 0.06)
+            .window(Grade::Bad, 0.1, 0.05);
+
+        assert_eq!(windows.judge(0.0), Some(Grade::Perfect));
+        assert_eq!(windows.judge(-0.016), Some(Grade::Great));
+        assert_eq!(windows.judge(0.055), Some(Grade::Great));
+        assert_eq!(windows.judge(-0.06), Some(Grade::Bad));
+        assert_eq!(windows.judge(0.06), None);
+        assert_eq!(windows.judge(-0.1), None);
+        assert_eq!(windows.early(), 0.1);
+        assert_eq!(windows.late(), 0.06);
+    }
+}
@@ -1,8 +1,19 @@
 //! This is the core library for the rhythm games.
 //! It contains the basic structures and traits that are used in the rhythm games.
+//!
+//! The crate is `no_std` and only needs `alloc`, so it also runs on embedded targets and wasm without std.
 
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod judge;
 pub mod note;
 pub mod rhythm;
+pub mod stats;
+pub mod transform;
+pub mod validate;
 
+pub use judge::*;
 pub use note::*;
 pub use rhythm::*;
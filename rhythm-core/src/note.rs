@@ -1,10 +1,10 @@
-use std::cmp::Ordering;
+use core::cmp::Ordering;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// The `Note` trait represents a rhythm note. (combo notes can be seen as a single note with volume > 1)
-pub trait Note: std::fmt::Debug + Ord + Clone {
+pub trait Note: core::fmt::Debug + Ord + Clone {
     /// Returns the start time of the note.
     fn start(&self) -> f64;
 
@@ -37,12 +37,12 @@ pub trait Note: std::fmt::Debug + Ord + Clone {
             .partial_cmp(&other.start())
             .unwrap_or(Ordering::Equal)
         {
-            std::cmp::Ordering::Equal => {
+            Ordering::Equal => {
                 match (self.start() + self.duration())
                     .partial_cmp(&(other.start() + other.duration()))
                     .unwrap_or(Ordering::Equal)
                 {
-                    std::cmp::Ordering::Equal => self.variant().into().cmp(&other.variant().into()),
+                    Ordering::Equal => self.variant().into().cmp(&other.variant().into()),
                     other => other,
                 }
             }
@@ -54,16 +54,23 @@ pub trait Note: std::fmt::Debug + Ord + Clone {
     fn matches_variant(&self, variant: impl Into<u16>) -> bool {
         self.variant().into() == variant.into()
     }
+
+    /// Returns whether the note has to be held from its start to its end, instead of being hit.
+    /// The volume of a hold note tracks its state: 2 before the head is hit, 1 while it is held, and 0 once it is released.
+    fn is_hold(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-#[cfg(feature = "serde")]
-#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SimpleNote {
     pub start: f64,
     pub duration: f64,
     pub volume: u16,
     pub variant: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hold: bool,
 }
 
 impl Eq for SimpleNote {}
@@ -80,6 +87,22 @@ impl SimpleNote {
             duration: duration.into(),
             volume: volume.into(),
             variant: variant.into(),
+            hold: false,
+        }
+    }
+
+    /// Creates a hold note, which has to be held from `start` to `start + duration`.
+    pub fn new_hold(
+        start: impl Into<f64>,
+        duration: impl Into<f64>,
+        variant: impl Into<u16>,
+    ) -> Self {
+        Self {
+            start: start.into(),
+            duration: duration.into(),
+            volume: 2,
+            variant: variant.into(),
+            hold: true,
         }
     }
 }
@@ -110,6 +133,10 @@ impl Note for SimpleNote {
     fn set_variant(&mut self, variant: impl Into<u16>) {
         self.variant = variant.into();
     }
+
+    fn is_hold(&self) -> bool {
+        self.hold
+    }
 }
 
 impl Ord for SimpleNote {
@@ -120,6 +147,6 @@ impl Ord for SimpleNote {
 
 impl PartialOrd for SimpleNote {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(Note::cmp(self, other))
+        Some(Ord::cmp(self, other))
     }
 }
@@ -1,38 +1,264 @@
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 
 use crate::note::Note;
 
+/// The score function of [`Selection::Custom`].
+pub type SelectionScore<T> = Arc<dyn Fn(&T, f64) -> f64 + Send + Sync>;
+
+/// The policy used by [`Rhythm::hit`] to pick a note when several hittable notes match the hit.
+pub enum Selection<T> {
+    /// The first matching note in sort order.
+    Earliest,
+    /// The matching note whose center (`start + duration / 2`) is nearest to the current time.
+    Nearest,
+    /// The matching note with the lowest score given by the function, called with the note and the current time.
+    /// Ties go to the earlier note. See [`Selection::custom`].
+    Custom(SelectionScore<T>),
+}
+
+impl<T> Selection<T> {
+    /// A [`Selection::Custom`] policy from a closure, which may capture state.
+    pub fn custom(score: impl Fn(&T, f64) -> f64 + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(score))
+    }
+}
+
+// Deriving `Clone` would require `T: Clone`.
+impl<T> Clone for Selection<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Earliest => Self::Earliest,
+            Self::Nearest => Self::Nearest,
+            Self::Custom(score) => Self::Custom(score.clone()),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Selection<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Earliest => f.write_str("Earliest"),
+            Self::Nearest => f.write_str("Nearest"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+// Deriving `Default` would require `T: Default`.
+#[allow(clippy::derivable_impls)]
+impl<T> Default for Selection<T> {
+    fn default() -> Self {
+        Self::Earliest
+    }
+}
+
+/// Whether an earlier hittable note blocks hits on the notes after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NoteLock {
+    /// Hits are never blocked.
+    #[default]
+    Off,
+    /// A hit is rejected if the selected note has an earlier hittable note of the same variant.
+    Variant,
+    /// A hit is rejected if the selected note has any earlier hittable note.
+    All,
+}
+
+/// The result of [`Rhythm::release`].
+#[derive(Debug, PartialEq)]
+pub enum Release<'a, T> {
+    /// The hold note was released within the tail window, with the time since its end (negative if released before it).
+    Tail(&'a mut T, f64),
+    /// The hold note was released before its tail window, with the time that was left until its end.
+    Early(&'a mut T, f64),
+}
+
+/// The volume of a hold note whose head has been hit and that is being held.
+const HOLDING: u16 = 1;
+
+/// The default of [`Rhythm::hold_window`]: 100 ms, about the widest hit window of the mania and taiko engines.
+/// It assumes note times in seconds, as in every chart format of this workspace; set the window for other units.
+pub const DEFAULT_HOLD_WINDOW: f64 = 0.1;
+
+/// The playback state of a [`Rhythm`]: the current time and the notes that have been started or hit.
+/// It does not contain the chart itself, so it is cheap to take for save states and rollback.
 #[derive(Debug, Clone, PartialEq)]
-#[cfg(feature = "serde")]
-#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RhythmState<T> {
+    pub time: f64,
+    pub cursor: usize,
+    pub active: Vec<usize>,
+    /// The hit notes, by their index in the sorted chart.
+    pub touched: Vec<(usize, T)>,
+}
+
+/// The indices of a deserialized rhythm are checked like the ones of [`Rhythm::restore`],
+/// so a corrupted save fails to load instead of panicking later.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(remote = "Self"))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "T: Deserialize<'de>")))]
 pub struct Rhythm<T: Note> {
     pub notes: Vec<T>,
     /// The current time.
     time: f64,
-    /// The available notes. When single notes are hit or combo notes not in range, they will be removed from this list.
-    #[serde(skip_serializing, skip_deserializing)]
-    availables: Vec<T>,
+    /// The notes sorted by start time, holding their current state (e.g. remaining volume).
+    sorted: Vec<T>,
+    /// `order[i]` is the index in `notes` of `sorted[i]`.
+    order: Vec<usize>,
+    /// Index of the first note in `sorted` that has not started yet.
+    cursor: usize,
+    /// Indices (ascending) of the started notes that are still available.
+    /// When single notes are hit or combo notes not in range, they will be removed from this list.
+    active: Vec<usize>,
+    /// Indices of the notes in `sorted` that have been hit since the last reset.
+    touched: Vec<usize>,
+    /// One past the last note in `sorted` with a non-zero volume.
+    live_end: usize,
+    /// The longest duration among the notes, bounds the backward search of `set_time`.
+    max_duration: f64,
+    /// How `hit` picks a note among the matching ones.
+    /// Not serialized, a deserialized rhythm uses the default selection. Not compared either, as closures can not be.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    selection: Selection<T>,
+    /// Whether earlier hittable notes block `hit`.
+    note_lock: NoteLock,
+    /// How far from their head and tail hold notes can be pressed and released.
+    hold_window: f64,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Note + Serialize> Serialize for Rhythm<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Note + Deserialize<'de>> Deserialize<'de> for Rhythm<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rhythm = Self::deserialize(deserializer)?;
+        let len = rhythm.notes.len();
+        let mut order = rhythm.order.clone();
+        order.sort_unstable();
+        if rhythm.sorted.len() != len || !order.into_iter().eq(0..len) || rhythm.live_end > len {
+            return Err(serde::de::Error::custom("invalid rhythm"));
+        }
+        check_state(
+            len,
+            rhythm.cursor,
+            &rhythm.active,
+            rhythm.touched.iter().copied(),
+        )
+        .map_err(serde::de::Error::custom)?;
+        Ok(rhythm)
+    }
+}
+
+/// Checks that the indices of a playback state are within `len` notes.
+fn check_state(
+    len: usize,
+    cursor: usize,
+    active: &[usize],
+    mut touched: impl Iterator<Item = usize>,
+) -> Result<(), &'static str> {
+    if cursor > len || active.iter().any(|&idx| idx >= cursor) || touched.any(|idx| idx >= len) {
+        return Err("The state does not belong to this rhythm");
+    }
+    Ok(())
+}
+
+impl<T: Note> PartialEq for Rhythm<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.notes == other.notes
+            && self.time == other.time
+            && self.sorted == other.sorted
+            && self.order == other.order
+            && self.cursor == other.cursor
+            && self.active == other.active
+            && self.touched == other.touched
+            && self.live_end == other.live_end
+            && self.max_duration == other.max_duration
+            && self.note_lock == other.note_lock
+            && self.hold_window == other.hold_window
+    }
 }
 
 impl<T: Note> Rhythm<T> {
     pub fn new(notes: Vec<T>) -> Self {
-        let mut availables = notes.clone();
-        availables.sort_unstable();
+        let mut order = (0..notes.len()).collect::<Vec<_>>();
+        order.sort_unstable_by(|&a, &b| Ord::cmp(&notes[a], &notes[b]));
+        let sorted = order.iter().map(|&i| notes[i].clone()).collect::<Vec<_>>();
+
+        let live_end = sorted
+            .iter()
+            .rposition(|note| note.volume() > 0)
+            .map_or(0, |i| i + 1);
+        let max_duration = sorted
+            .iter()
+            .map(|note| note.duration())
+            .fold(0.0, f64::max);
 
         Self {
             notes,
             time: 0.0,
-            availables,
+            sorted,
+            order,
+            cursor: 0,
+            active: vec![],
+            touched: vec![],
+            live_end,
+            max_duration,
+            selection: Selection::default(),
+            note_lock: NoteLock::default(),
+            hold_window: DEFAULT_HOLD_WINDOW,
         }
     }
 
+    /// Sets the policy used by [`Rhythm::hit`] to pick a note. Defaults to [`Selection::Earliest`].
+    pub fn selection(mut self, selection: Selection<T>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets the note-lock rule used by [`Rhythm::hit`]. Defaults to [`NoteLock::Off`].
+    pub fn note_lock(mut self, note_lock: NoteLock) -> Self {
+        self.note_lock = note_lock;
+        self
+    }
+
+    /// Sets how far from their head and tail hold notes can be pressed and released.
+    /// Defaults to [`DEFAULT_HOLD_WINDOW`]. A window of `0` makes hold notes practically unhittable.
+    pub fn hold_window(mut self, hold_window: f64) -> Self {
+        self.hold_window = hold_window;
+        self
+    }
+
     pub fn current_time(&self) -> f64 {
         self.time
     }
 
-    pub fn availables(&self) -> &[T] {
-        &self.availables
+    /// Returns the available notes in sort order: the started notes that are still hittable, followed by the upcoming notes.
+    pub fn availables(&self) -> impl Iterator<Item = &T> {
+        self.availables_until(f64::INFINITY)
+    }
+
+    /// Same as [`Rhythm::availables`], but stops at the first upcoming note that starts after `until`.
+    pub fn availables_until(&self, until: f64) -> impl Iterator<Item = &T> {
+        let upcoming = &self.sorted[self.cursor..];
+        let end = upcoming.partition_point(|note| note.start() <= until);
+
+        self.active
+            .iter()
+            .map(|&idx| &self.sorted[idx])
+            .chain(upcoming[..end].iter().filter(|note| note.volume() > 0))
     }
 
     pub fn forward(&mut self, time: impl Into<f64>) -> Vec<T> {
@@ -42,36 +268,165 @@ impl<T: Note> Rhythm<T> {
 
     pub fn set_time(&mut self, time: impl Into<f64>) {
         self.time = time.into();
-        self.availables.clone_from(&self.notes);
-        self.availables.sort_unstable();
+
+        for idx in core::mem::take(&mut self.touched) {
+            self.sorted[idx] = self.notes[self.order[idx]].clone();
+        }
+
+        let time = self.time;
+        let lead = self.hold_window;
+        self.cursor = self
+            .sorted
+            .partition_point(|note| note.start() - lead <= time);
+        let from = self.sorted[..self.cursor]
+            .partition_point(|note| note.start() < time - self.max_duration - lead);
+        self.active = (from..self.cursor)
+            .filter(|&idx| self.sorted[idx].volume() > 0)
+            .collect();
         self.update_availables();
     }
 
     pub fn finished(&self) -> bool {
-        self.availables.is_empty()
+        self.active.is_empty() && self.cursor >= self.live_end
     }
 
+    /// Hits (presses) a note of the given variant at the current time.
+    /// The note is picked among the hittable ones by the [`Selection`] policy,
+    /// and the hit is rejected (returns `None`) when it is blocked by the [`NoteLock`] rule.
+    /// Returns the hit note and the time since its start, which is negative for hold notes pressed before their head.
     pub fn hit(&mut self, variant: impl Into<u16>) -> Option<(&mut T, f64)> {
         let variant: u16 = variant.into();
-        let hitables = self.availables.iter_mut().filter(|note| {
-            note.start() <= self.time && note.start() + note.duration() >= self.time
+        let time = self.time;
+        let window = self.hold_window;
+
+        let mut candidates = self.active.iter().copied().filter(|&idx| {
+            let note = &self.sorted[idx];
+            hittable(note, time, window) && note.matches_variant(variant)
         });
+        let idx = match &self.selection {
+            Selection::Earliest => candidates.next(),
+            Selection::Nearest => candidates.min_by(|&a, &b| {
+                let distance = |note: &T| (time - center(note)).abs();
+                distance(&self.sorted[a]).total_cmp(&distance(&self.sorted[b]))
+            }),
+            Selection::Custom(score) => candidates.min_by(|&a, &b| {
+                score(&self.sorted[a], time).total_cmp(&score(&self.sorted[b], time))
+            }),
+        }?;
 
-        for note in hitables {
-            if note.matches_variant(variant) && note.volume() > 0 {
-                note.set_volume(note.volume() - 1);
-                let time = self.time - note.start();
-                return Some((note, time));
+        let locked = self.active.iter().take_while(|&&i| i < idx).any(|&i| {
+            let note = &self.sorted[i];
+            match self.note_lock {
+                NoteLock::Off => false,
+                NoteLock::Variant => hittable(note, time, window) && note.matches_variant(variant),
+                NoteLock::All => hittable(note, time, window),
             }
+        });
+        if locked {
+            return None;
+        }
+
+        if self.sorted[idx].volume() == self.notes[self.order[idx]].volume() {
+            self.touched.push(idx);
+        }
+
+        let note = &mut self.sorted[idx];
+        if note.is_hold() {
+            note.set_volume(HOLDING);
+        } else {
+            note.set_volume(note.volume() - 1);
+        }
+        let delta = time - note.start();
+        Some((note, delta))
+    }
+
+    /// Releases the held note of the given variant at the current time.
+    /// Returns `None` if no hold note of the variant is being held.
+    pub fn release(&mut self, variant: impl Into<u16>) -> Option<Release<'_, T>> {
+        let variant: u16 = variant.into();
+        let time = self.time;
+        let idx = self.active.iter().copied().find(|&idx| {
+            let note = &self.sorted[idx];
+            note.is_hold() && note.volume() == HOLDING && note.matches_variant(variant)
+        })?;
+
+        let note = &mut self.sorted[idx];
+        note.set_volume(0);
+        let end = note.start() + note.duration();
+        if time < end - self.hold_window {
+            Some(Release::Early(note, end - time))
+        } else {
+            Some(Release::Tail(note, time - end))
         }
+    }
 
-        None
+    /// Takes a snapshot of the playback state.
+    pub fn snapshot(&self) -> RhythmState<T> {
+        RhythmState {
+            time: self.time,
+            cursor: self.cursor,
+            active: self.active.clone(),
+            touched: self
+                .touched
+                .iter()
+                .map(|&idx| (idx, self.sorted[idx].clone()))
+                .collect(),
+        }
     }
 
+    /// Restores a playback state taken by [`Rhythm::snapshot`] on a rhythm with the same notes.
+    pub fn restore(&mut self, state: RhythmState<T>) -> Result<(), &'static str> {
+        check_state(
+            self.sorted.len(),
+            state.cursor,
+            &state.active,
+            state.touched.iter().map(|(idx, _)| *idx),
+        )?;
+
+        for idx in core::mem::take(&mut self.touched) {
+            self.sorted[idx] = self.notes[self.order[idx]].clone();
+        }
+        for (idx, note) in state.touched {
+            self.sorted[idx] = note;
+            self.touched.push(idx);
+        }
+
+        self.time = state.time;
+        self.cursor = state.cursor;
+        self.active = state.active;
+        Ok(())
+    }
+
+    /// Moves the newly started notes into `active` and removes the ones that are no longer available.
+    /// Only the notes around the current time are visited.
+    ///
+    /// A removed hold note with a volume of 2 missed its head,
+    /// and one with a volume of 1 was still held when its tail window closed.
     fn update_availables(&mut self) -> Vec<T> {
+        while self.cursor < self.sorted.len()
+            && self.sorted[self.cursor].start() - self.hold_window <= self.time
+        {
+            if self.sorted[self.cursor].volume() > 0 {
+                self.active.push(self.cursor);
+            }
+            self.cursor += 1;
+        }
+
+        let time = self.time;
+        let window = self.hold_window;
+        let sorted = &self.sorted;
         let mut removed = vec![];
-        self.availables.retain(|note| {
-            let keep = note.start() + note.duration() >= self.time && note.volume() > 0;
+        self.active.retain(|&idx| {
+            let note = &sorted[idx];
+            let keep = if note.volume() == 0 {
+                false
+            } else if !note.is_hold() {
+                note.start() + note.duration() >= time
+            } else if note.volume() == HOLDING {
+                note.start() + note.duration() + window >= time
+            } else {
+                note.start() + window >= time
+            };
             if !keep && note.volume() > 0 {
                 removed.push(note.clone());
             }
@@ -81,6 +436,24 @@ impl<T: Note> Rhythm<T> {
     }
 }
 
+/// Whether the note can be hit at the given time.
+fn hittable<T: Note>(note: &T, time: f64, hold_window: f64) -> bool {
+    if note.is_hold() {
+        note.volume() > HOLDING && (time - note.start()).abs() <= hold_window
+    } else {
+        note.start() <= time && note.start() + note.duration() >= time && note.volume() > 0
+    }
+}
+
+/// The time a hit on the note aims at.
+fn center<T: Note>(note: &T) -> f64 {
+    if note.is_hold() {
+        note.start()
+    } else {
+        note.start() + note.duration() / 2.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +539,290 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_set_time() {
+        let notes = vec![
+            SimpleNote::new(2000, 100, 1u16, 0u16),
+            SimpleNote::new(1000, 2000, 3u16, 1u16),
+            SimpleNote::new(3000, 100, 1u16, 0u16),
+        ];
+
+        let mut rhythm = Rhythm::new(notes);
+
+        assert_eq!(rhythm.forward(2050), vec![]);
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(2000, 100, 0u16, 0u16), 50.0))
+        );
+        assert_eq!(
+            rhythm.hit(1u16),
+            Some((&mut SimpleNote::new(1000, 2000, 2u16, 1u16), 1050.0))
+        );
+        assert_eq!(
+            rhythm.forward(1000),
+            vec![SimpleNote::new(1000, 2000, 2u16, 1u16)]
+        );
+        assert_eq!(rhythm.availables().count(), 1);
+
+        // going back restores the hit notes
+        rhythm.set_time(1500);
+        assert_eq!(
+            rhythm.availables().cloned().collect::<Vec<_>>(),
+            vec![
+                SimpleNote::new(1000, 2000, 3u16, 1u16),
+                SimpleNote::new(2000, 100, 1u16, 0u16),
+                SimpleNote::new(3000, 100, 1u16, 0u16),
+            ]
+        );
+        assert_eq!(
+            rhythm.hit(1u16),
+            Some((&mut SimpleNote::new(1000, 2000, 2u16, 1u16), 500.0))
+        );
+
+        rhythm.set_time(5000);
+        assert_eq!(rhythm.availables().count(), 0);
+        assert!(rhythm.finished());
+    }
+
+    #[test]
+    fn test_availables_until() {
+        let notes = (0..100)
+            .map(|i| SimpleNote::new(i * 100, 0, 1u16, 0u16))
+            .collect::<Vec<_>>();
+
+        let mut rhythm = Rhythm::new(notes);
+
+        rhythm.forward(1000);
+        assert_eq!(
+            rhythm
+                .availables_until(1250.0)
+                .map(|note| note.start)
+                .collect::<Vec<_>>(),
+            vec![1000.0, 1100.0, 1200.0]
+        );
+        assert_eq!(rhythm.availables().count(), 90);
+        assert!(!rhythm.finished());
+
+        assert_eq!(rhythm.forward(9000).len(), 90);
+        assert!(rhythm.finished());
+    }
+
+    #[test]
+    fn test_nearest_selection() {
+        let notes = vec![
+            SimpleNote::new(1000, 200, 1u16, 0u16),
+            SimpleNote::new(1100, 200, 1u16, 0u16),
+        ];
+
+        let mut rhythm = Rhythm::new(notes.clone());
+        rhythm.forward(1180);
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(1000, 200, 0u16, 0u16), 180.0))
+        );
+
+        let mut rhythm = Rhythm::new(notes.clone()).selection(Selection::Nearest);
+        rhythm.forward(1180);
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(1100, 200, 0u16, 0u16), 80.0))
+        );
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(1000, 200, 0u16, 0u16), 180.0))
+        );
+
+        // prefer the latest note
+        let mut rhythm = Rhythm::new(notes.clone())
+            .selection(Selection::custom(|note: &SimpleNote, _| -note.start));
+        rhythm.forward(1150);
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(1100, 200, 0u16, 0u16), 50.0))
+        );
+
+        // prefer the note closest to a captured target time
+        let target = 1010.0;
+        let mut rhythm =
+            Rhythm::new(notes).selection(Selection::custom(move |note: &SimpleNote, _| {
+                (note.start - target).abs()
+            }));
+        rhythm.forward(1150);
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(1000, 200, 0u16, 0u16), 150.0))
+        );
+    }
+
+    #[test]
+    fn test_note_lock() {
+        let notes = vec![
+            SimpleNote::new(1000, 200, 1u16, 0u16),
+            SimpleNote::new(1050, 200, 1u16, 1u16),
+            SimpleNote::new(1100, 200, 1u16, 0u16),
+        ];
+
+        let mut rhythm = Rhythm::new(notes.clone())
+            .selection(Selection::Nearest)
+            .note_lock(NoteLock::Variant);
+        rhythm.forward(1190);
+        assert_eq!(rhythm.hit(0u16), None);
+        assert_eq!(
+            rhythm.hit(1u16),
+            Some((&mut SimpleNote::new(1050, 200, 0u16, 1u16), 140.0))
+        );
+
+        let mut rhythm = Rhythm::new(notes)
+            .selection(Selection::Nearest)
+            .note_lock(NoteLock::All);
+        rhythm.forward(1120);
+        assert_eq!(rhythm.hit(1u16), None);
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(1000, 200, 0u16, 0u16), 120.0))
+        );
+        assert_eq!(
+            rhythm.hit(1u16),
+            Some((&mut SimpleNote::new(1050, 200, 0u16, 1u16), 70.0))
+        );
+        assert_eq!(
+            rhythm.hit(0u16),
+            Some((&mut SimpleNote::new(1100, 200, 0u16, 0u16), 20.0))
+        );
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let notes = vec![
+            SimpleNote::new(1000, 100, 1u16, 0u16),
+            SimpleNote::new(1000, 1000, 5u16, 1u16),
+            SimpleNote::new(1500, 100, 1u16, 0u16),
+        ];
+
+        let mut rhythm = Rhythm::new(notes);
+        rhythm.forward(1050);
+        rhythm.hit(0u16);
+        rhythm.hit(1u16);
+
+        let state = rhythm.snapshot();
+        let expected = rhythm.clone();
+
+        rhythm.hit(1u16);
+        rhythm.forward(500);
+        rhythm.hit(0u16);
+        assert_ne!(rhythm, expected);
+
+        rhythm.restore(state.clone()).unwrap();
+        assert_eq!(rhythm, expected);
+
+        let mut other = Rhythm::new(vec![SimpleNote::new(0, 0, 1u16, 0u16)]);
+        assert!(other.restore(state).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_roundtrip() {
+        let notes = vec![
+            SimpleNote::new(1000, 100, 1u16, 0u16),
+            SimpleNote::new(1000, 1000, 5u16, 1u16),
+            SimpleNote::new(1500, 100, 1u16, 0u16),
+        ];
+
+        let mut rhythm = Rhythm::new(notes);
+        rhythm.forward(1050);
+        rhythm.hit(0u16);
+        rhythm.hit(1u16);
+
+        let json = serde_json::to_string(&rhythm).unwrap();
+        let mut restored: Rhythm<SimpleNote> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, rhythm);
+        assert!(!restored.finished());
+
+        assert_eq!(restored.forward(500), rhythm.forward(500));
+        assert_eq!(restored.hit(0u16), rhythm.hit(0u16));
+        assert_eq!(restored.hit(1u16), rhythm.hit(1u16));
+
+        // a corrupted save fails to load
+        let mut value = serde_json::to_value(&rhythm).unwrap();
+        for (field, corrupted) in [
+            ("cursor", serde_json::json!(5)),
+            ("active", serde_json::json!([0, 7])),
+            ("touched", serde_json::json!([3])),
+            ("order", serde_json::json!([0, 0, 1])),
+            ("sorted", serde_json::json!([])),
+            ("live_end", serde_json::json!(4)),
+        ] {
+            let original = value[field].clone();
+            value[field] = corrupted;
+            assert!(serde_json::from_value::<Rhythm<SimpleNote>>(value.clone()).is_err());
+            value[field] = original;
+        }
+        assert!(serde_json::from_value::<Rhythm<SimpleNote>>(value).is_ok());
+    }
+
+    #[test]
+    fn test_default_hold_window() {
+        let mut rhythm = Rhythm::new(vec![SimpleNote::new_hold(1.0, 0.5, 0u16)]);
+        rhythm.forward(0.95);
+        assert!(rhythm.hit(0u16).is_some());
+        rhythm.forward(0.6);
+        assert!(matches!(rhythm.release(0u16), Some(Release::Tail(_, _))));
+    }
+
+    #[test]
+    fn test_hold_note() {
+        let notes = vec![
+            SimpleNote::new_hold(1000, 500, 0u16),
+            SimpleNote::new_hold(2000, 500, 1u16),
+            SimpleNote::new_hold(3000, 500, 0u16),
+            SimpleNote::new_hold(4000, 500, 1u16),
+        ];
+
+        let mut rhythm = Rhythm::new(notes).hold_window(50.0);
+
+        // pressed early, released in the tail window
+        rhythm.forward(970);
+        assert_eq!(rhythm.release(0u16), None);
+        let (note, delta) = rhythm.hit(0u16).unwrap();
+        assert_eq!((note.volume, delta), (1, -30.0));
+        assert_eq!(rhythm.hit(0u16), None);
+        assert_eq!(rhythm.forward(500), vec![]);
+        assert_eq!(
+            rhythm.release(0u16),
+            Some(Release::Tail(
+                &mut SimpleNote {
+                    volume: 0,
+                    ..SimpleNote::new_hold(1000, 500, 0u16)
+                },
+                -30.0
+            ))
+        );
+
+        // released early
+        rhythm.forward(570);
+        assert!(rhythm.hit(1u16).is_some());
+        rhythm.forward(200);
+        assert!(matches!(rhythm.release(1u16), Some(Release::Early(_, left)) if left == 260.0));
+
+        // held through the tail window
+        rhythm.forward(800);
+        assert!(rhythm.hit(0u16).is_some());
+        assert_eq!(rhythm.forward(500), vec![]);
+        assert_eq!(
+            rhythm.forward(20),
+            vec![SimpleNote {
+                volume: 1,
+                ..SimpleNote::new_hold(3000, 500, 0u16)
+            }]
+        );
+
+        // missed head
+        assert_eq!(rhythm.forward(480), vec![]);
+        assert_eq!(
+            rhythm.forward(20),
+            vec![SimpleNote::new_hold(4000, 500, 1u16)]
+        );
+        assert!(rhythm.finished());
+    }
 }
@@ -0,0 +1,158 @@
+//! Density and distribution statistics of charts.
+//!
+//! All functions take the notes in any order and count each note once, at its start time.
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::Note;
+
+fn starts<N: Note>(notes: &[N]) -> Vec<f64> {
+    let mut starts = notes.iter().map(|note| note.start()).collect::<Vec<_>>();
+    starts.sort_by(f64::total_cmp);
+    starts
+}
+
+/// Returns the time from the start of the first note to the end of the last note.
+pub fn length<N: Note>(notes: &[N]) -> f64 {
+    let start = notes.iter().map(|note| note.start()).reduce(f64::min);
+    let end = notes
+        .iter()
+        .map(|note| note.start() + note.duration())
+        .reduce(f64::max);
+    match (start, end) {
+        (Some(start), Some(end)) => end - start,
+        _ => 0.0,
+    }
+}
+
+/// Returns the average number of notes per second over the length of the chart.
+pub fn average_nps<N: Note>(notes: &[N]) -> f64 {
+    let length = length(notes);
+    if length > 0.0 {
+        notes.len() as f64 / length
+    } else {
+        0.0
+    }
+}
+
+/// The most buckets [`nps_over_time`] returns.
+pub const MAX_BUCKETS: usize = 1 << 20;
+
+/// Returns the notes per second of each `step` seconds long bucket, starting from the first note.
+/// Notes at non-finite times are skipped. Returns no buckets if `step` is not positive and finite,
+/// or if the chart needs more than [`MAX_BUCKETS`] of them.
+pub fn nps_over_time<N: Note>(notes: &[N], step: f64) -> Vec<f64> {
+    if !(step.is_finite() && step > 0.0) {
+        return vec![];
+    }
+    let mut starts = starts(notes);
+    starts.retain(|start| start.is_finite());
+    let (first, last) = match (starts.first(), starts.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return vec![],
+    };
+    let span = (last - first) / step;
+    if span >= MAX_BUCKETS as f64 {
+        return vec![];
+    }
+
+    let mut buckets = vec![0usize; span as usize + 1];
+    for start in starts {
+        buckets[((start - first) / step) as usize] += 1;
+    }
+    buckets
+        .into_iter()
+        .map(|count| count as f64 / step)
+        .collect()
+}
+
+/// Returns the start time and the note count of the `window` seconds long span with the most notes.
+/// The span is `[start, start + window)`, and always starts at a note.
+pub fn peak_density<N: Note>(notes: &[N], window: f64) -> Option<(f64, usize)> {
+    let starts = starts(notes);
+    let mut peak: Option<(f64, usize)> = None;
+    let mut end = 0;
+    for (i, start) in starts.iter().enumerate() {
+        while end < starts.len() && starts[end] < start + window {
+            end += 1;
+        }
+        if peak.is_none_or(|(_, count)| end - i > count) {
+            peak = Some((*start, end - i));
+        }
+    }
+    peak
+}
+
+/// Returns the length of the longest run of notes where each note starts within `max_gap` seconds of the previous one.
+pub fn longest_stream<N: Note>(notes: &[N], max_gap: f64) -> usize {
+    let starts = starts(notes);
+    let mut longest = starts.len().min(1);
+    let mut current = longest;
+    for pair in starts.windows(2) {
+        if pair[1] - pair[0] <= max_gap {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 1;
+        }
+    }
+    longest
+}
+
+/// Returns the number of notes of each variant.
+pub fn variant_distribution<N: Note>(notes: &[N]) -> BTreeMap<u16, usize> {
+    let mut distribution = BTreeMap::new();
+    for note in notes {
+        *distribution.entry(note.variant().into()).or_insert(0) += 1;
+    }
+    distribution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleNote;
+
+    #[test]
+    fn test_stats() {
+        // a burst of 8 notes every 0.125s from 1.0, then a note at 4.0 and a hold from 5.0 to 6.0
+        let mut notes = (0..8)
+            .map(|i| SimpleNote::new(1.0 + i as f64 * 0.125, 0.0, 1u16, (i % 2) as u16))
+            .collect::<Vec<_>>();
+        notes.push(SimpleNote::new(4.0, 0.0, 1u16, 2u16));
+        notes.push(SimpleNote::new_hold(5.0, 1.0, 2u16));
+        notes.reverse();
+
+        assert_eq!(length(&notes), 5.0);
+        assert_eq!(average_nps(&notes), 2.0);
+        assert_eq!(nps_over_time(&notes, 1.0), vec![8.0, 0.0, 0.0, 1.0, 1.0]);
+        for step in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(nps_over_time(&notes, step), Vec::<f64>::new());
+        }
+        assert_eq!(peak_density(&notes, 1.0), Some((1.0, 8)));
+        assert_eq!(peak_density(&notes, 0.5), Some((1.0, 4)));
+        assert_eq!(longest_stream(&notes, 0.125), 8);
+        assert_eq!(longest_stream(&notes, 1.0), 8);
+        assert_eq!(longest_stream(&notes, 2.5), 10);
+        assert_eq!(
+            variant_distribution(&notes),
+            BTreeMap::from([(0, 4), (1, 4), (2, 2)])
+        );
+
+        // a note at a non-finite time is skipped, and a far outlier does not allocate
+        notes.push(SimpleNote::new(f64::INFINITY, 0.0, 1u16, 0u16));
+        notes.push(SimpleNote::new(f64::NAN, 0.0, 1u16, 0u16));
+        assert_eq!(nps_over_time(&notes, 1.0), vec![8.0, 0.0, 0.0, 1.0, 1.0]);
+        notes.push(SimpleNote::new(1e12, 0.0, 1u16, 0u16));
+        assert_eq!(nps_over_time(&notes, 0.001), Vec::<f64>::new());
+        assert_eq!(nps_over_time(&notes, 1e7).len(), 100_000);
+
+        let empty: Vec<SimpleNote> = vec![];
+        assert_eq!(length(&empty), 0.0);
+        assert_eq!(nps_over_time(&empty, 1.0), Vec::<f64>::new());
+        assert_eq!(peak_density(&empty, 1.0), None);
+        assert_eq!(longest_stream(&empty, 1.0), 0);
+    }
+}
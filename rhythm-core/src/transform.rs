@@ -0,0 +1,153 @@
+//! Transforms of note collections, for adjusting offsets and creating practice variants of charts.
+//!
+//! Every transform is deterministic: the same input (and seed) always gives the same output.
+
+use alloc::vec::Vec;
+
+use crate::Note;
+
+/// Moves all notes by `offset` seconds.
+pub fn offset<N: Note>(notes: &mut [N], offset: f64) {
+    for note in notes.iter_mut() {
+        note.set_start(note.start() + offset);
+    }
+}
+
+/// Changes the playback rate of the notes, e.g. 1.5 plays the chart 1.5 times as fast.
+/// Both start times and durations are scaled, so notes keep their relative lengths.
+pub fn scale<N: Note>(notes: &mut [N], rate: f64) -> Result<(), &'static str> {
+    if !(rate > 0.0 && rate.is_finite()) {
+        return Err("rate must be positive");
+    }
+    for note in notes.iter_mut() {
+        note.set_start(note.start() / rate);
+        note.set_duration(note.duration() / rate);
+    }
+    Ok(())
+}
+
+/// Replaces the variant of every note with `map(variant)`.
+pub fn remap<N: Note>(notes: &mut [N], map: impl Fn(u16) -> u16) {
+    for note in notes.iter_mut() {
+        let variant = note.variant().into();
+        note.set_variant(map(variant));
+    }
+}
+
+/// Reverses the order of `variants`, e.g. `[0, 1, 2, 3]` mirrors the lanes of a 4K chart,
+/// and `[1, 2]` swaps dons and kats. Other variants are kept.
+pub fn mirror<N: Note>(notes: &mut [N], variants: &[u16]) {
+    remap(notes, |variant| {
+        match variants.iter().position(|v| *v == variant) {
+            Some(i) => variants[variants.len() - 1 - i],
+            None => variant,
+        }
+    });
+}
+
+/// Swaps `variants` with each other by a random permutation generated from `seed`.
+/// Other variants are kept.
+pub fn shuffle<N: Note>(notes: &mut [N], variants: &[u16], seed: u64) {
+    let mut rng = SplitMix64(seed);
+    let mut permutation = variants.to_vec();
+    // Fisher-Yates
+    for i in (1..permutation.len()).rev() {
+        let j = (rng.next() % (i as u64 + 1)) as usize;
+        permutation.swap(i, j);
+    }
+
+    remap(notes, |variant| {
+        match variants.iter().position(|v| *v == variant) {
+            Some(i) => permutation[i],
+            None => variant,
+        }
+    });
+}
+
+/// Returns the notes starting within `[start, end)`.
+pub fn slice<N: Note>(notes: &[N], start: f64, end: f64) -> Vec<N> {
+    notes
+        .iter()
+        .filter(|note| note.start() >= start && note.start() < end)
+        .cloned()
+        .collect()
+}
+
+/// A small seeded generator, so transforms do not depend on a random number crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleNote;
+
+    fn chart() -> Vec<SimpleNote> {
+        vec![
+            SimpleNote::new(1.0, 0.0, 1u16, 0u16),
+            SimpleNote::new(1.5, 0.0, 1u16, 1u16),
+            SimpleNote::new_hold(2.0, 1.0, 2u16),
+            SimpleNote::new(3.0, 0.0, 1u16, 3u16),
+            SimpleNote::new(3.5, 0.0, 1u16, 9u16),
+        ]
+    }
+
+    fn variants(notes: &[SimpleNote]) -> Vec<u16> {
+        notes.iter().map(|note| note.variant).collect()
+    }
+
+    #[test]
+    fn test_timing() {
+        let mut notes = chart();
+        offset(&mut notes, -0.5);
+        assert_eq!(notes[0].start, 0.5);
+        assert_eq!(notes[2].start, 1.5);
+
+        scale(&mut notes, 2.0).unwrap();
+        assert_eq!((notes[2].start, notes[2].duration), (0.75, 0.5));
+        assert_eq!(notes[3].start, 1.25);
+        assert!(scale(&mut notes, 0.0).is_err());
+        assert!(scale(&mut notes, f64::NAN).is_err());
+
+        let notes = chart();
+        let sliced = slice(&notes, 1.5, 3.0);
+        assert_eq!(sliced, notes[1..3].to_vec());
+    }
+
+    #[test]
+    fn test_variants() {
+        let mut notes = chart();
+        mirror(&mut notes, &[0, 1, 2, 3]);
+        assert_eq!(variants(&notes), vec![3, 2, 1, 0, 9]);
+
+        let mut notes = chart();
+        remap(&mut notes, |variant| variant * 2);
+        assert_eq!(variants(&notes), vec![0, 2, 4, 6, 18]);
+
+        let mut a = chart();
+        let mut b = chart();
+        shuffle(&mut a, &[0, 1, 2, 3], 42);
+        shuffle(&mut b, &[0, 1, 2, 3], 42);
+        assert_eq!(a, b);
+        assert_eq!(a[4].variant, 9);
+        let mut lanes = variants(&a)[..4].to_vec();
+        lanes.sort();
+        assert_eq!(lanes, vec![0, 1, 2, 3]);
+
+        // some seed has to move some lane
+        assert!((0..16).any(|seed| {
+            let mut notes = chart();
+            shuffle(&mut notes, &[0, 1, 2, 3], seed);
+            variants(&notes) != variants(&chart())
+        }));
+    }
+}
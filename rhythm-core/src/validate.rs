@@ -0,0 +1,160 @@
+//! Validation of charts before they are played.
+//!
+//! Which notes conflict depends on the layout of the chart (see [`Lanes`]): on a single lane, as in taiko,
+//! every pair of notes does, while on a lane per variant, as in mania, only notes sharing a lane do.
+//! Notes with a volume of 0, such as bar lines and events, can not be hit and never conflict.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::Note;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IssueKind {
+    /// The note starts before 0 or has a negative duration.
+    NegativeTime,
+    /// The note starts at the same time as a conflicting note.
+    Duplicate,
+    /// The note starts inside the duration of a conflicting note.
+    Overlap,
+    /// The note starts exactly at the end of a conflicting note, leaving no time to finish one and hit the other.
+    ZeroGap,
+}
+
+/// The layout of a chart, which decides which notes conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Lanes {
+    /// All notes are on one lane, whatever their variant: a don and a kat at the same time are duplicates,
+    /// and any note inside a drumroll or balloon overlaps it.
+    #[default]
+    Single,
+    /// Every variant has its own lane, and two notes conflict when either of them matches the other's variant
+    /// (see [`Note::matches_variant`]). Chords on different lanes are valid.
+    PerVariant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Diagnostic {
+    pub kind: IssueKind,
+    /// The index of the offending note in the validated slice.
+    pub index: usize,
+    /// The index of the conflicting note, which is always the earlier one.
+    pub other: Option<usize>,
+    /// The start time of the offending note.
+    pub time: f64,
+}
+
+fn conflicts<N: Note>(lanes: Lanes, a: &N, b: &N) -> bool {
+    if a.volume() == 0 || b.volume() == 0 {
+        return false;
+    }
+    match lanes {
+        Lanes::Single => true,
+        Lanes::PerVariant => a.matches_variant(b.variant()) || b.matches_variant(a.variant()),
+    }
+}
+
+/// Checks the notes for negative times, duplicates, overlaps and zero gaps.
+/// The diagnostics are ordered by the time of the offending note; an empty result means the chart is valid.
+pub fn validate<N: Note>(notes: &[N], lanes: Lanes) -> Vec<Diagnostic> {
+    let mut order = (0..notes.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        notes[*a]
+            .start()
+            .total_cmp(&notes[*b].start())
+            .then(a.cmp(b))
+    });
+
+    let mut diagnostics = vec![];
+    // notes that started before the current one, and did not end before it
+    let mut open: Vec<usize> = vec![];
+
+    for index in order {
+        let note = &notes[index];
+        let time = note.start();
+        let mut report = |kind, other| {
+            diagnostics.push(Diagnostic {
+                kind,
+                index,
+                other,
+                time,
+            })
+        };
+
+        if time < 0.0 || note.duration() < 0.0 {
+            report(IssueKind::NegativeTime, None);
+        }
+
+        open.retain(|&other| notes[other].start() + notes[other].duration() >= time);
+        for &other in open.iter() {
+            if !conflicts(lanes, &notes[other], note) {
+                continue;
+            }
+            let start = notes[other].start();
+            let end = start + notes[other].duration();
+            if start == time {
+                report(IssueKind::Duplicate, Some(other));
+            } else if end > time {
+                report(IssueKind::Overlap, Some(other));
+            } else if notes[other].duration() > 0.0 {
+                report(IssueKind::ZeroGap, Some(other));
+            }
+        }
+        open.push(index);
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleNote;
+
+    #[test]
+    fn test_validate() {
+        let notes = vec![
+            SimpleNote::new(-0.5, 0.0, 1u16, 0u16),
+            SimpleNote::new(1.0, 0.0, 1u16, 0u16),
+            SimpleNote::new(1.0, 0.0, 1u16, 1u16),
+            SimpleNote::new(1.0, 0.0, 1u16, 0u16),
+            SimpleNote::new_hold(2.0, 1.0, 2u16),
+            SimpleNote::new(2.5, 0.0, 1u16, 2u16),
+            SimpleNote::new(2.5, 0.0, 1u16, 3u16),
+            SimpleNote::new(3.0, 0.0, 1u16, 2u16),
+            SimpleNote::new(4.0, -1.0, 1u16, 0u16),
+        ];
+
+        let kinds = validate(&notes, Lanes::PerVariant)
+            .iter()
+            .map(|d| (d.kind, d.index, d.other))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (IssueKind::NegativeTime, 0, None),
+                (IssueKind::Duplicate, 3, Some(1)),
+                (IssueKind::Overlap, 5, Some(4)),
+                (IssueKind::ZeroGap, 7, Some(4)),
+                (IssueKind::NegativeTime, 8, None),
+            ]
+        );
+
+        assert!(validate(&notes[1..3], Lanes::PerVariant).is_empty());
+
+        // on a single lane, different variants conflict too
+        let kinds = validate(&notes[1..3], Lanes::Single)
+            .iter()
+            .map(|d| (d.kind, d.index, d.other))
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![(IssueKind::Duplicate, 1, Some(0))]);
+        let bar_line = SimpleNote::new(1.0, 0.0, 0u16, 1u16);
+        assert!(validate(&[notes[1], bar_line], Lanes::Single).is_empty());
+    }
+}
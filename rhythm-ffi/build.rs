@@ -0,0 +1,18 @@
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=RHYTHM_FFI_UPDATE_HEADER");
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let bindings = cbindgen::generate(&crate_dir).expect("failed to generate the C header");
+    bindings.write_to_file(out_dir.join("rhythm.h"));
+
+    // the checked-in header is only rewritten on request, so builds never touch the source tree
+    if env::var_os("RHYTHM_FFI_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(crate_dir.join("include/rhythm.h"));
+    }
+}
@@ -0,0 +1,315 @@
+/* Generated by cbindgen from rhythm-ffi, do not edit. */
+
+#ifndef RHYTHM_H
+#define RHYTHM_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum RhythmHit {
+  RHYTHM_HIT_NONE,
+  /**
+   * A don on an unknown side.
+   */
+  RHYTHM_HIT_DON,
+  /**
+   * A kat on an unknown side.
+   */
+  RHYTHM_HIT_KAT,
+  RHYTHM_HIT_DON_LEFT,
+  RHYTHM_HIT_DON_RIGHT,
+  RHYTHM_HIT_KAT_LEFT,
+  RHYTHM_HIT_KAT_RIGHT,
+} RhythmHit;
+
+typedef enum RhythmJudgement {
+  /**
+   * Nothing was judged in the frame.
+   */
+  RHYTHM_JUDGEMENT_NONE,
+  RHYTHM_JUDGEMENT_GREAT,
+  RHYTHM_JUDGEMENT_OK,
+  RHYTHM_JUDGEMENT_MISS,
+  RHYTHM_JUDGEMENT_COMBO_HIT,
+  /**
+   * The hit did not hit any note.
+   */
+  RHYTHM_JUDGEMENT_NOTHING,
+  /**
+   * The second hit on a big note.
+   */
+  RHYTHM_JUDGEMENT_GREAT_BIG,
+  RHYTHM_JUDGEMENT_OK_BIG,
+  RHYTHM_JUDGEMENT_BALLOON_POPPED,
+} RhythmJudgement;
+
+/**
+ * The kind of a note, see `TaikoNoteType`.
+ */
+typedef enum RhythmNoteType {
+  RHYTHM_NOTE_TYPE_SMALL,
+  RHYTHM_NOTE_TYPE_BIG,
+  RHYTHM_NOTE_TYPE_SMALL_COMBO,
+  RHYTHM_NOTE_TYPE_BIG_COMBO,
+  RHYTHM_NOTE_TYPE_BALLOON,
+  RHYTHM_NOTE_TYPE_YAM,
+  RHYTHM_NOTE_TYPE_GOGO_START,
+  RHYTHM_NOTE_TYPE_GOGO_END,
+  RHYTHM_NOTE_TYPE_BAR_LINE,
+} RhythmNoteType;
+
+/**
+ * A taiko game in progress.
+ */
+typedef struct RhythmTaikoEngine RhythmTaikoEngine;
+
+/**
+ * A parsed TJA file.
+ */
+typedef struct RhythmTja RhythmTja;
+
+/**
+ * A course of a TJA file. Missing values are -1.
+ */
+typedef struct RhythmCourse {
+  /**
+   * 0 for easy, 1 for normal, 2 for hard, 3 for oni, 4 for edit.
+   */
+  int32_t course;
+  int32_t level;
+  int32_t scoreinit;
+  int32_t scorediff;
+  /**
+   * 0 for AC1-AC7, 1 for AC8-AC14 and 2 for shin-uchi.
+   */
+  int32_t scoremode;
+  size_t note_count;
+} RhythmCourse;
+
+typedef struct RhythmNote {
+  /**
+   * in seconds
+   */
+  double start;
+  /**
+   * in seconds
+   */
+  double duration;
+  uint16_t volume;
+  /**
+   * 1 for don, 2 for kat, 3 for both, 127 for invisible events.
+   */
+  uint16_t variant;
+  enum RhythmNoteType note_type;
+  /**
+   * bpm * scroll
+   */
+  float speed;
+} RhythmNote;
+
+typedef struct RhythmTaikoOutput {
+  bool finished;
+  uint32_t score;
+  uint32_t current_combo;
+  uint32_t max_combo;
+  double gauge;
+  /**
+   * The judgement of the last hit.
+   */
+  enum RhythmJudgement judgement;
+  /**
+   * The number of judgements of the hits, see [`rhythm_taiko_engine_judgements`].
+   */
+  size_t judgement_count;
+  /**
+   * The number of notes to draw, see [`rhythm_taiko_engine_display`].
+   */
+  size_t display_count;
+} RhythmTaikoOutput;
+
+/**
+ * A hit at `time`, in seconds.
+ */
+typedef struct RhythmTimedHit {
+  double time;
+  enum RhythmHit hit;
+} RhythmTimedHit;
+
+/**
+ * A note on screen, with the positions of its head and tail from 0 (judgement point) to 1 (right edge).
+ */
+typedef struct RhythmDisplayNote {
+  struct RhythmNote note;
+  double head;
+  double tail;
+} RhythmDisplayNote;
+
+typedef struct RhythmTaikoFinal {
+  uint32_t score;
+  uint32_t max_combo;
+  double gauge;
+  uint32_t greats;
+  uint32_t goods;
+  uint32_t misses;
+  uint32_t max_hit;
+  /**
+   * The balloons that ended before they were popped. Kusudamas are not failed when they end unpopped.
+   */
+  uint32_t unpopped;
+  bool passed;
+} RhythmTaikoFinal;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the message of the last error on this thread, or `NULL` if nothing failed yet.
+ * The message is valid until the next failing call on the same thread.
+ */
+const char *rhythm_last_error(void);
+
+/**
+ * Parses a TJA file from `len` bytes of UTF-8 or Shift-JIS text.
+ * Returns `NULL` on failure. The result has to be released with [`rhythm_tja_free`].
+ *
+ * # Safety
+ *
+ * `data` must be valid for `len` reads.
+ */
+struct RhythmTja *rhythm_tja_parse(const uint8_t *data, size_t len);
+
+/**
+ * Releases a TJA returned by [`rhythm_tja_parse`]. `NULL` is ignored.
+ *
+ * # Safety
+ *
+ * `tja` must be `NULL` or a pointer returned by [`rhythm_tja_parse`] that is not released yet.
+ */
+void rhythm_tja_free(struct RhythmTja *tja);
+
+/**
+ * Returns the number of courses of the TJA.
+ *
+ * # Safety
+ *
+ * `tja` must be `NULL` or a valid TJA.
+ */
+size_t rhythm_tja_course_count(const struct RhythmTja *tja);
+
+/**
+ * Writes the course at `index` to `out`. Returns `false` if the index is out of range.
+ *
+ * # Safety
+ *
+ * `tja` must be `NULL` or a valid TJA, and `out` must be `NULL` or valid for writes.
+ */
+bool rhythm_tja_course(const struct RhythmTja *tja, size_t index, struct RhythmCourse *out);
+
+/**
+ * Copies up to `capacity` notes of the course at `index` to `out`, invisible events included,
+ * and returns the total number of notes. Pass `NULL` as `out` to only get the number.
+ *
+ * # Safety
+ *
+ * `tja` must be `NULL` or a valid TJA, and `out` must be `NULL` or valid for `capacity` writes.
+ */
+size_t rhythm_tja_notes(const struct RhythmTja *tja,
+                        size_t index,
+                        struct RhythmNote *out,
+                        size_t capacity);
+
+/**
+ * Creates an engine playing the course at `index` of the TJA.
+ * Note times are shifted by the `OFFSET` of the TJA, so the engine runs on the time of the music.
+ * Returns `NULL` if the course does not exist. The result has to be released with [`rhythm_taiko_engine_free`].
+ *
+ * # Safety
+ *
+ * `tja` must be `NULL` or a valid TJA.
+ */
+struct RhythmTaikoEngine *rhythm_taiko_engine_new(const struct RhythmTja *tja,
+                                                  size_t index);
+
+/**
+ * Releases an engine returned by [`rhythm_taiko_engine_new`]. `NULL` is ignored.
+ *
+ * # Safety
+ *
+ * `engine` must be `NULL` or a pointer returned by [`rhythm_taiko_engine_new`] that is not released yet.
+ */
+void rhythm_taiko_engine_free(struct RhythmTaikoEngine *engine);
+
+/**
+ * Advances the engine to `time` (in seconds) with an optional hit at `time`, and writes the state to `out`.
+ * Fails if `time` is not finite.
+ *
+ * # Safety
+ *
+ * `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for writes.
+ */
+bool rhythm_taiko_engine_forward(struct RhythmTaikoEngine *engine,
+                                 double time,
+                                 enum RhythmHit hit,
+                                 struct RhythmTaikoOutput *out);
+
+/**
+ * Advances the engine to `time` (in seconds) with `count` hits, each judged at its own time,
+ * and writes the state to `out`. The judgement in `out` is the one of the last hit,
+ * see [`rhythm_taiko_engine_judgements`] for all of them.
+ * Fails if `time` or the time of a hit is not finite.
+ *
+ * # Safety
+ *
+ * `engine` must be `NULL` or a valid engine, `hits` must be valid for `count` reads (or `NULL` if `count` is 0),
+ * and `out` must be `NULL` or valid for writes.
+ */
+bool rhythm_taiko_engine_forward_hits(struct RhythmTaikoEngine *engine,
+                                      double time,
+                                      const struct RhythmTimedHit *hits,
+                                      size_t count,
+                                      struct RhythmTaikoOutput *out);
+
+/**
+ * Copies up to `capacity` notes on screen after the last [`rhythm_taiko_engine_forward`] to `out`,
+ * and returns the total number of notes on screen. Pass `NULL` as `out` to only get the number.
+ *
+ * # Safety
+ *
+ * `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for `capacity` writes.
+ */
+size_t rhythm_taiko_engine_display(const struct RhythmTaikoEngine *engine,
+                                   struct RhythmDisplayNote *out,
+                                   size_t capacity);
+
+/**
+ * Copies up to `capacity` judgements of the hits of the last [`rhythm_taiko_engine_forward`]
+ * or [`rhythm_taiko_engine_forward_hits`] to `out`, in time order, and returns the total number of judgements.
+ * Pass `NULL` as `out` to only get the number.
+ *
+ * # Safety
+ *
+ * `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for `capacity` writes.
+ */
+size_t rhythm_taiko_engine_judgements(const struct RhythmTaikoEngine *engine,
+                                      enum RhythmJudgement *out,
+                                      size_t capacity);
+
+/**
+ * Writes the result of the game to `out`.
+ *
+ * # Safety
+ *
+ * `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for writes.
+ */
+bool rhythm_taiko_engine_finalize(const struct RhythmTaikoEngine *engine,
+                                  struct RhythmTaikoFinal *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RHYTHM_H */
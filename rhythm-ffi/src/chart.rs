@@ -0,0 +1,204 @@
+use tja::{TJAParser, TaikoNote, TaikoNoteType, TJA};
+
+use crate::{copy_out, guard, set_error};
+
+/// A parsed TJA file.
+pub struct RhythmTja(pub(crate) TJA);
+
+/// The kind of a note, see `TaikoNoteType`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RhythmNoteType {
+    Small,
+    Big,
+    SmallCombo,
+    BigCombo,
+    Balloon,
+    Yam,
+    GogoStart,
+    GogoEnd,
+    BarLine,
+}
+
+impl From<TaikoNoteType> for RhythmNoteType {
+    fn from(value: TaikoNoteType) -> Self {
+        match value {
+            TaikoNoteType::Small => RhythmNoteType::Small,
+            TaikoNoteType::Big => RhythmNoteType::Big,
+            TaikoNoteType::SmallCombo => RhythmNoteType::SmallCombo,
+            TaikoNoteType::BigCombo => RhythmNoteType::BigCombo,
+            TaikoNoteType::Balloon => RhythmNoteType::Balloon,
+            TaikoNoteType::Yam => RhythmNoteType::Yam,
+            TaikoNoteType::GogoStart => RhythmNoteType::GogoStart,
+            TaikoNoteType::GogoEnd => RhythmNoteType::GogoEnd,
+            TaikoNoteType::BarLine => RhythmNoteType::BarLine,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RhythmNote {
+    /// in seconds
+    pub start: f64,
+    /// in seconds
+    pub duration: f64,
+    pub volume: u16,
+    /// 1 for don, 2 for kat, 3 for both, 127 for invisible events.
+    pub variant: u16,
+    pub note_type: RhythmNoteType,
+    /// bpm * scroll
+    pub speed: f32,
+}
+
+impl From<&TaikoNote> for RhythmNote {
+    fn from(note: &TaikoNote) -> Self {
+        Self {
+            start: note.start,
+            duration: note.duration,
+            volume: note.volume,
+            variant: note.variant.into(),
+            note_type: note.note_type.into(),
+            speed: note.speed,
+        }
+    }
+}
+
+/// A course of a TJA file. Missing values are -1.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RhythmCourse {
+    /// 0 for easy, 1 for normal, 2 for hard, 3 for oni, 4 for edit.
+    pub course: i32,
+    pub level: i32,
+    pub scoreinit: i32,
+    pub scorediff: i32,
+    /// 0 for AC1-AC7, 1 for AC8-AC14 and 2 for shin-uchi.
+    pub scoremode: i32,
+    pub note_count: usize,
+}
+
+fn decode(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.strip_prefix('\u{feff}').unwrap_or(text).to_string(),
+        Err(_) => encoding_rs::SHIFT_JIS.decode(bytes).0.into_owned(),
+    }
+}
+
+/// Parses a TJA file from `len` bytes of UTF-8 or Shift-JIS text.
+/// Returns `NULL` on failure. The result has to be released with [`rhythm_tja_free`].
+///
+/// # Safety
+///
+/// `data` must be valid for `len` reads.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_tja_parse(data: *const u8, len: usize) -> *mut RhythmTja {
+    guard(std::ptr::null_mut(), || {
+        if data.is_null() {
+            set_error("data is null");
+            return std::ptr::null_mut();
+        }
+        let content = decode(std::slice::from_raw_parts(data, len));
+        match TJAParser::new().parse(content) {
+            Ok(tja) => Box::into_raw(Box::new(RhythmTja(tja))),
+            Err(error) => {
+                set_error(error);
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Releases a TJA returned by [`rhythm_tja_parse`]. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `tja` must be `NULL` or a pointer returned by [`rhythm_tja_parse`] that is not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_tja_free(tja: *mut RhythmTja) {
+    guard((), || {
+        if !tja.is_null() {
+            drop(Box::from_raw(tja));
+        }
+    })
+}
+
+/// Returns the number of courses of the TJA.
+///
+/// # Safety
+///
+/// `tja` must be `NULL` or a valid TJA.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_tja_course_count(tja: *const RhythmTja) -> usize {
+    guard(0, || match tja.as_ref() {
+        Some(tja) => tja.0.courses.len(),
+        None => 0,
+    })
+}
+
+/// Writes the course at `index` to `out`. Returns `false` if the index is out of range.
+///
+/// # Safety
+///
+/// `tja` must be `NULL` or a valid TJA, and `out` must be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_tja_course(
+    tja: *const RhythmTja,
+    index: usize,
+    out: *mut RhythmCourse,
+) -> bool {
+    guard(false, || {
+        let course = match tja.as_ref().and_then(|tja| tja.0.courses.get(index)) {
+            Some(course) => course,
+            None => {
+                set_error("course not found");
+                return false;
+            }
+        };
+        if out.is_null() {
+            set_error("out is null");
+            return false;
+        }
+
+        out.write(RhythmCourse {
+            course: course.course,
+            level: course.level.unwrap_or(-1),
+            scoreinit: course.scoreinit.unwrap_or(-1),
+            scorediff: course.scorediff.unwrap_or(-1),
+            scoremode: course.scoremode.unwrap_or(-1),
+            note_count: course.note_count(),
+        });
+        true
+    })
+}
+
+/// Copies up to `capacity` notes of the course at `index` to `out`, invisible events included,
+/// and returns the total number of notes. Pass `NULL` as `out` to only get the number.
+///
+/// # Safety
+///
+/// `tja` must be `NULL` or a valid TJA, and `out` must be `NULL` or valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_tja_notes(
+    tja: *const RhythmTja,
+    index: usize,
+    out: *mut RhythmNote,
+    capacity: usize,
+) -> usize {
+    guard(0, || {
+        match tja.as_ref().and_then(|tja| tja.0.courses.get(index)) {
+            Some(course) => {
+                let notes = course
+                    .notes
+                    .iter()
+                    .map(RhythmNote::from)
+                    .collect::<Vec<_>>();
+                copy_out(&notes, out, capacity)
+            }
+            None => {
+                set_error("course not found");
+                0
+            }
+        }
+    })
+}
@@ -0,0 +1,373 @@
+use rhythm_core::transform;
+use taiko_core::{
+    DefaultTaikoEngine, Final, GameSource, Hit, InputState, Judgement, OutputState, TaikoEngine,
+};
+
+use crate::chart::{RhythmNote, RhythmTja};
+use crate::{copy_out, guard, set_error};
+
+/// A taiko game in progress.
+pub struct RhythmTaikoEngine {
+    engine: DefaultTaikoEngine,
+    display: Vec<RhythmDisplayNote>,
+    judgements: Vec<RhythmJudgement>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RhythmHit {
+    None,
+    /// A don on an unknown side.
+    Don,
+    /// A kat on an unknown side.
+    Kat,
+    DonLeft,
+    DonRight,
+    KatLeft,
+    KatRight,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RhythmJudgement {
+    /// Nothing was judged in the frame.
+    None,
+    Great,
+    Ok,
+    Miss,
+    ComboHit,
+    /// The hit did not hit any note.
+    Nothing,
+    /// The second hit on a big note.
+    GreatBig,
+    OkBig,
+    BalloonPopped,
+}
+
+impl From<Option<Judgement>> for RhythmJudgement {
+    fn from(value: Option<Judgement>) -> Self {
+        match value {
+            None => RhythmJudgement::None,
+            Some(Judgement::Great) => RhythmJudgement::Great,
+            Some(Judgement::Ok) => RhythmJudgement::Ok,
+            Some(Judgement::Miss) => RhythmJudgement::Miss,
+            Some(Judgement::ComboHit) => RhythmJudgement::ComboHit,
+            Some(Judgement::Nothing) => RhythmJudgement::Nothing,
+            Some(Judgement::GreatBig) => RhythmJudgement::GreatBig,
+            Some(Judgement::OkBig) => RhythmJudgement::OkBig,
+            Some(Judgement::BalloonPopped) => RhythmJudgement::BalloonPopped,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RhythmTaikoOutput {
+    pub finished: bool,
+    pub score: u32,
+    pub current_combo: u32,
+    pub max_combo: u32,
+    pub gauge: f64,
+    /// The judgement of the last hit.
+    pub judgement: RhythmJudgement,
+    /// The number of judgements of the hits, see [`rhythm_taiko_engine_judgements`].
+    pub judgement_count: usize,
+    /// The number of notes to draw, see [`rhythm_taiko_engine_display`].
+    pub display_count: usize,
+}
+
+/// A note on screen, with the positions of its head and tail from 0 (judgement point) to 1 (right edge).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RhythmDisplayNote {
+    pub note: RhythmNote,
+    pub head: f64,
+    pub tail: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RhythmTaikoFinal {
+    pub score: u32,
+    pub max_combo: u32,
+    pub gauge: f64,
+    pub greats: u32,
+    pub goods: u32,
+    pub misses: u32,
+    pub max_hit: u32,
+    /// The balloons that ended before they were popped. Kusudamas are not failed when they end unpopped.
+    pub unpopped: u32,
+    pub passed: bool,
+}
+
+impl From<Final> for RhythmTaikoFinal {
+    fn from(value: Final) -> Self {
+        Self {
+            score: value.score,
+            max_combo: value.max_combo,
+            gauge: value.gauge,
+            greats: value.greats,
+            goods: value.goods,
+            misses: value.misses,
+            max_hit: value.max_hit,
+            unpopped: value.unpopped,
+            passed: value.passed,
+        }
+    }
+}
+
+/// Creates an engine playing the course at `index` of the TJA.
+/// Note times are shifted by the `OFFSET` of the TJA, so the engine runs on the time of the music.
+/// Returns `NULL` if the course does not exist. The result has to be released with [`rhythm_taiko_engine_free`].
+///
+/// # Safety
+///
+/// `tja` must be `NULL` or a valid TJA.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_taiko_engine_new(
+    tja: *const RhythmTja,
+    index: usize,
+) -> *mut RhythmTaikoEngine {
+    guard(std::ptr::null_mut(), || {
+        let tja = match tja.as_ref() {
+            Some(tja) => &tja.0,
+            None => {
+                set_error("tja is null");
+                return std::ptr::null_mut();
+            }
+        };
+        let course = match tja.courses.get(index) {
+            Some(course) => course,
+            None => {
+                set_error("course not found");
+                return std::ptr::null_mut();
+            }
+        };
+
+        let mut source = GameSource::from(course);
+        transform::offset(&mut source.notes, -tja.header.offset.unwrap_or(0.0) as f64);
+
+        Box::into_raw(Box::new(RhythmTaikoEngine {
+            engine: DefaultTaikoEngine::new(source),
+            display: vec![],
+            judgements: vec![],
+        }))
+    })
+}
+
+/// Releases an engine returned by [`rhythm_taiko_engine_new`]. `NULL` is ignored.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a pointer returned by [`rhythm_taiko_engine_new`] that is not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_taiko_engine_free(engine: *mut RhythmTaikoEngine) {
+    guard((), || {
+        if !engine.is_null() {
+            drop(Box::from_raw(engine));
+        }
+    })
+}
+
+/// A hit at `time`, in seconds.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RhythmTimedHit {
+    pub time: f64,
+    pub hit: RhythmHit,
+}
+
+impl From<RhythmHit> for Option<Hit> {
+    fn from(value: RhythmHit) -> Self {
+        match value {
+            RhythmHit::None => None,
+            RhythmHit::Don => Some(Hit::Don),
+            RhythmHit::Kat => Some(Hit::Kat),
+            RhythmHit::DonLeft => Some(Hit::DonLeft),
+            RhythmHit::DonRight => Some(Hit::DonRight),
+            RhythmHit::KatLeft => Some(Hit::KatLeft),
+            RhythmHit::KatRight => Some(Hit::KatRight),
+        }
+    }
+}
+
+/// Advances the engine to `time` (in seconds) with an optional hit at `time`, and writes the state to `out`.
+/// Fails if `time` is not finite.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_taiko_engine_forward(
+    engine: *mut RhythmTaikoEngine,
+    time: f64,
+    hit: RhythmHit,
+    out: *mut RhythmTaikoOutput,
+) -> bool {
+    guard(false, || {
+        let hit: Option<Hit> = hit.into();
+        let hits = hit.map(|hit| vec![(time, hit)]).unwrap_or_default();
+        forward(engine, time, hits, out)
+    })
+}
+
+/// Advances the engine to `time` (in seconds) with `count` hits, each judged at its own time,
+/// and writes the state to `out`. The judgement in `out` is the one of the last hit,
+/// see [`rhythm_taiko_engine_judgements`] for all of them.
+/// Fails if `time` or the time of a hit is not finite.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a valid engine, `hits` must be valid for `count` reads (or `NULL` if `count` is 0),
+/// and `out` must be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_taiko_engine_forward_hits(
+    engine: *mut RhythmTaikoEngine,
+    time: f64,
+    hits: *const RhythmTimedHit,
+    count: usize,
+    out: *mut RhythmTaikoOutput,
+) -> bool {
+    guard(false, || {
+        let hits = if count == 0 {
+            &[][..]
+        } else if hits.is_null() {
+            set_error("hits is null");
+            return false;
+        } else {
+            std::slice::from_raw_parts(hits, count)
+        };
+        if hits.iter().any(|timed| !timed.time.is_finite()) {
+            set_error("hit time is not finite");
+            return false;
+        }
+        let hits = hits
+            .iter()
+            .filter_map(|timed| Option::<Hit>::from(timed.hit).map(|hit| (timed.time, hit)))
+            .collect();
+        forward(engine, time, hits, out)
+    })
+}
+
+unsafe fn forward(
+    engine: *mut RhythmTaikoEngine,
+    time: f64,
+    hits: Vec<(f64, Hit)>,
+    out: *mut RhythmTaikoOutput,
+) -> bool {
+    let engine = match engine.as_mut() {
+        Some(engine) => engine,
+        None => {
+            set_error("engine is null");
+            return false;
+        }
+    };
+    if !time.is_finite() {
+        set_error("time is not finite");
+        return false;
+    }
+
+    let output: OutputState = engine.engine.forward(InputState { time, hits });
+
+    engine.display = output
+        .display
+        .iter()
+        .filter_map(|note| {
+            note.position(time).map(|(head, tail)| RhythmDisplayNote {
+                note: RhythmNote::from(&note.inner),
+                head,
+                tail,
+            })
+        })
+        .collect();
+    engine.judgements = output
+        .judgements
+        .iter()
+        .map(|&judgement| Some(judgement).into())
+        .collect();
+
+    if !out.is_null() {
+        out.write(RhythmTaikoOutput {
+            finished: output.finished,
+            score: output.score,
+            current_combo: output.current_combo,
+            max_combo: output.max_combo,
+            gauge: output.gauge,
+            judgement: output.judgement.into(),
+            judgement_count: engine.judgements.len(),
+            display_count: engine.display.len(),
+        });
+    }
+    true
+}
+
+/// Copies up to `capacity` notes on screen after the last [`rhythm_taiko_engine_forward`] to `out`,
+/// and returns the total number of notes on screen. Pass `NULL` as `out` to only get the number.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_taiko_engine_display(
+    engine: *const RhythmTaikoEngine,
+    out: *mut RhythmDisplayNote,
+    capacity: usize,
+) -> usize {
+    guard(0, || match engine.as_ref() {
+        Some(engine) => copy_out(&engine.display, out, capacity),
+        None => {
+            set_error("engine is null");
+            0
+        }
+    })
+}
+
+/// Copies up to `capacity` judgements of the hits of the last [`rhythm_taiko_engine_forward`]
+/// or [`rhythm_taiko_engine_forward_hits`] to `out`, in time order, and returns the total number of judgements.
+/// Pass `NULL` as `out` to only get the number.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_taiko_engine_judgements(
+    engine: *const RhythmTaikoEngine,
+    out: *mut RhythmJudgement,
+    capacity: usize,
+) -> usize {
+    guard(0, || match engine.as_ref() {
+        Some(engine) => copy_out(&engine.judgements, out, capacity),
+        None => {
+            set_error("engine is null");
+            0
+        }
+    })
+}
+
+/// Writes the result of the game to `out`.
+///
+/// # Safety
+///
+/// `engine` must be `NULL` or a valid engine, and `out` must be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rhythm_taiko_engine_finalize(
+    engine: *const RhythmTaikoEngine,
+    out: *mut RhythmTaikoFinal,
+) -> bool {
+    guard(false, || {
+        let engine = match engine.as_ref() {
+            Some(engine) => engine,
+            None => {
+                set_error("engine is null");
+                return false;
+            }
+        };
+        if out.is_null() {
+            set_error("out is null");
+            return false;
+        }
+
+        out.write(engine.engine.finalize().into());
+        true
+    })
+}
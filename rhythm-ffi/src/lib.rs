@@ -0,0 +1,85 @@
+//! The C API of the TJA parser and the taiko game core.
+//!
+//! Objects are created by `rhythm_*_new` / `rhythm_tja_parse` and have to be released by the matching `*_free` function.
+//! Functions that fail return `NULL`, `false` or `0`, and the reason can be read with [`rhythm_last_error`].
+//! A panic inside a function is caught before it reaches C, and reported as a failure with a `panic: ` error.
+//!
+//! The header is generated by cbindgen into the build directory when the crate is built.
+//! The checked-in copy `include/rhythm.h` is only rewritten when building with `RHYTHM_FFI_UPDATE_HEADER=1`,
+//! and a test checks that it is up to date.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+pub mod chart;
+pub mod engine;
+
+pub use chart::*;
+pub use engine::*;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(error: &str) {
+    LAST_ERROR.with(|last| {
+        last.replace(CString::new(error).ok());
+    });
+}
+
+/// Runs the body of an exported function, returning `failed` with a `panic: ` error if it panics,
+/// as unwinding into C is undefined behavior.
+fn guard<R>(failed: R, body: impl FnOnce() -> R) -> R {
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(result) => result,
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown");
+            set_error(&format!("panic: {message}"));
+            failed
+        }
+    }
+}
+
+/// Returns the message of the last error on this thread, or `NULL` if nothing failed yet.
+/// The message is valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn rhythm_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |error| error.as_ptr())
+    })
+}
+
+/// Copies `items` to `out`, up to `capacity` items, and returns the total number of items.
+/// Passing a `NULL` `out` only returns the number of items.
+///
+/// # Safety
+///
+/// `out` must be `NULL` or valid for `capacity` writes.
+unsafe fn copy_out<T: Copy>(items: &[T], out: *mut T, capacity: usize) -> usize {
+    if !out.is_null() {
+        let count = items.len().min(capacity);
+        std::ptr::copy_nonoverlapping(items.as_ptr(), out, count);
+    }
+    items.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn test_guard() {
+        assert_eq!(guard(0, || 1), 1);
+        assert!(!guard(false, || panic!("out of notes")));
+        let error = unsafe { CStr::from_ptr(rhythm_last_error()) };
+        assert_eq!(error.to_str(), Ok("panic: out of notes"));
+    }
+}
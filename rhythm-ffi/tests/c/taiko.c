@@ -0,0 +1,100 @@
+/* Plays a short chart through the C API. Built and run by tests/c_api.rs. */
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "rhythm.h"
+
+#define CHECK(cond)                                                   \
+    if (!(cond)) {                                                    \
+        const char *error = rhythm_last_error();                      \
+        fprintf(stderr, "%s:%d: check failed: %s (last error: %s)\n", \
+                __FILE__, __LINE__, #cond, error ? error : "none");   \
+        return 1;                                                     \
+    }
+
+static const char *TJA =
+    "TITLE:FFI\n"
+    "BPM:120\n"
+    "OFFSET:0\n"
+    "COURSE:Oni\n"
+    "LEVEL:8\n"
+    "#START\n"
+    "1212,\n"
+    "#END\n";
+
+int main(void) {
+    RhythmTja *tja = rhythm_tja_parse((const uint8_t *)TJA, strlen(TJA));
+    CHECK(tja != NULL);
+    CHECK(rhythm_tja_course_count(tja) == 1);
+
+    RhythmCourse course;
+    CHECK(rhythm_tja_course(tja, 0, &course));
+    CHECK(course.course == 3);
+    CHECK(course.level == 8);
+    CHECK(course.scoreinit == -1);
+    CHECK(course.scoremode == -1);
+    CHECK(course.note_count == 4);
+    CHECK(!rhythm_tja_course(tja, 1, &course));
+    CHECK(strcmp(rhythm_last_error(), "course not found") == 0);
+
+    /* bar line + 4 notes */
+    RhythmNote notes[8];
+    CHECK(rhythm_tja_notes(tja, 0, NULL, 0) == 5);
+    CHECK(rhythm_tja_notes(tja, 0, notes, 8) == 5);
+    CHECK(notes[0].note_type == RHYTHM_NOTE_TYPE_BAR_LINE);
+    CHECK(notes[2].variant == 2 && notes[2].start == 0.5);
+
+    RhythmTaikoEngine *engine = rhythm_taiko_engine_new(tja, 0);
+    CHECK(engine != NULL);
+    CHECK(rhythm_taiko_engine_new(tja, 1) == NULL);
+
+    RhythmTaikoOutput output;
+    RhythmHit hits[4] = {RHYTHM_HIT_DON, RHYTHM_HIT_KAT, RHYTHM_HIT_DON, RHYTHM_HIT_KAT};
+    for (int i = 0; i < 4; i++) {
+        CHECK(rhythm_taiko_engine_forward(engine, i * 0.5, hits[i], &output));
+        CHECK(output.judgement == RHYTHM_JUDGEMENT_GREAT);
+        CHECK(output.current_combo == (uint32_t)(i + 1));
+    }
+
+    RhythmDisplayNote display[8];
+    size_t count = rhythm_taiko_engine_display(engine, display, 8);
+    CHECK(count == output.display_count);
+
+    /* two hits in one step */
+    RhythmTaikoEngine *batched = rhythm_taiko_engine_new(tja, 0);
+    RhythmTimedHit timed[3] = {{0.0, RHYTHM_HIT_DON}, {0.3, RHYTHM_HIT_DON}, {0.5, RHYTHM_HIT_KAT}};
+    CHECK(rhythm_taiko_engine_forward_hits(batched, 0.6, timed, 3, &output));
+    CHECK(output.judgement == RHYTHM_JUDGEMENT_GREAT);
+    CHECK(output.current_combo == 2);
+    CHECK(output.judgement_count == 3);
+    RhythmJudgement judgements[3];
+    CHECK(rhythm_taiko_engine_judgements(batched, NULL, 0) == 3);
+    CHECK(rhythm_taiko_engine_judgements(batched, judgements, 3) == 3);
+    CHECK(judgements[0] == RHYTHM_JUDGEMENT_GREAT);
+    CHECK(judgements[1] == RHYTHM_JUDGEMENT_NOTHING);
+    CHECK(judgements[2] == RHYTHM_JUDGEMENT_GREAT);
+    CHECK(!rhythm_taiko_engine_forward_hits(batched, 0.7, NULL, 1, &output));
+    RhythmTimedHit nan_hit = {NAN, RHYTHM_HIT_DON};
+    CHECK(!rhythm_taiko_engine_forward_hits(batched, 0.7, &nan_hit, 1, &output));
+    CHECK(strcmp(rhythm_last_error(), "hit time is not finite") == 0);
+    CHECK(!rhythm_taiko_engine_forward(batched, NAN, RHYTHM_HIT_NONE, &output));
+    rhythm_taiko_engine_free(batched);
+
+    CHECK(rhythm_taiko_engine_forward(engine, 3.0, RHYTHM_HIT_NONE, &output));
+    CHECK(output.finished);
+    CHECK(output.judgement == RHYTHM_JUDGEMENT_NONE);
+
+    RhythmTaikoFinal result;
+    CHECK(rhythm_taiko_engine_finalize(engine, &result));
+    CHECK(result.greats == 4 && result.misses == 0);
+    CHECK(result.max_combo == 4);
+    CHECK(result.score > 0);
+
+    rhythm_taiko_engine_free(engine);
+    rhythm_tja_free(tja);
+
+    printf("ok\n");
+    return 0;
+}
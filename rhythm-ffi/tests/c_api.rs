@@ -0,0 +1,49 @@
+//! Builds `tests/c/taiko.c` against the generated header and the cdylib, and runs it.
+//! Also checks that the checked-in `include/rhythm.h` matches the generated header.
+
+#![cfg(unix)]
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn c_program() {
+    // the cdylib is built next to the test executable, in target/<profile>/deps
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let program = deps.join("rhythm-ffi-c-test");
+
+    let status = Command::new(env::var("CC").unwrap_or("cc".to_string()))
+        .arg(manifest.join("tests/c/taiko.c"))
+        .arg("-I")
+        .arg(env!("OUT_DIR"))
+        .arg("-L")
+        .arg(&deps)
+        .arg("-lrhythm")
+        .arg(format!("-Wl,-rpath,{}", deps.display()))
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success());
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let generated = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/rhythm.h")).unwrap();
+    let checked_in =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/include/rhythm.h")).unwrap();
+    assert!(
+        generated == checked_in,
+        "include/rhythm.h is outdated, rebuild with RHYTHM_FFI_UPDATE_HEADER=1"
+    );
+}
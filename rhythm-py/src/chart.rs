@@ -0,0 +1,217 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use tja::{TJACourse, TJAHeader, TJAParser, TaikoNote, TJA};
+
+#[pyclass(name = "TJAParser", module = "rhythm_rs")]
+pub struct PyTJAParser(TJAParser);
+
+#[pymethods]
+impl PyTJAParser {
+    #[new]
+    fn new() -> Self {
+        Self(TJAParser::new())
+    }
+
+    /// Parses the content of a TJA file, raising `ValueError` if it is invalid.
+    fn parse(&self, content: &str) -> PyResult<PyTJA> {
+        self.0
+            .parse(content)
+            .map(PyTJA)
+            .map_err(PyValueError::new_err)
+    }
+}
+
+#[pyclass(name = "TJA", module = "rhythm_rs")]
+#[derive(Clone)]
+pub struct PyTJA(pub TJA);
+
+#[pymethods]
+impl PyTJA {
+    #[getter]
+    fn header(&self) -> PyTJAHeader {
+        PyTJAHeader(self.0.header.clone())
+    }
+
+    #[getter]
+    fn courses(&self) -> Vec<PyTJACourse> {
+        self.0.courses.iter().cloned().map(PyTJACourse).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TJA(title={:?}, courses={})",
+            self.0.header.title,
+            self.0.courses.len()
+        )
+    }
+}
+
+#[pyclass(name = "TJAHeader", module = "rhythm_rs")]
+#[derive(Clone)]
+pub struct PyTJAHeader(pub TJAHeader);
+
+#[pymethods]
+impl PyTJAHeader {
+    #[getter]
+    fn title(&self) -> Option<String> {
+        self.0.title.clone()
+    }
+
+    #[getter]
+    fn subtitle(&self) -> Option<String> {
+        self.0.subtitle.clone()
+    }
+
+    #[getter]
+    fn bpm(&self) -> Option<f32> {
+        self.0.bpm
+    }
+
+    #[getter]
+    fn wave(&self) -> Option<String> {
+        self.0.wave.clone()
+    }
+
+    #[getter]
+    fn offset(&self) -> Option<f32> {
+        self.0.offset
+    }
+
+    #[getter]
+    fn demostart(&self) -> Option<f32> {
+        self.0.demostart
+    }
+
+    #[getter]
+    fn genre(&self) -> Option<String> {
+        self.0.genre.clone()
+    }
+
+    #[getter]
+    fn artist(&self) -> Option<String> {
+        self.0.artist.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[pyclass(name = "TJACourse", module = "rhythm_rs")]
+#[derive(Clone)]
+pub struct PyTJACourse(pub TJACourse);
+
+#[pymethods]
+impl PyTJACourse {
+    /// 0 for easy, 1 for normal, 2 for hard, 3 for oni, 4 for edit.
+    #[getter]
+    fn course(&self) -> i32 {
+        self.0.course
+    }
+
+    #[getter]
+    fn level(&self) -> Option<i32> {
+        self.0.level
+    }
+
+    #[getter]
+    fn scoreinit(&self) -> Option<i32> {
+        self.0.scoreinit
+    }
+
+    #[getter]
+    fn scorediff(&self) -> Option<i32> {
+        self.0.scorediff
+    }
+
+    /// 0 for AC1-AC7, 1 for AC8-AC14 and 2 for shin-uchi.
+    #[getter]
+    fn scoremode(&self) -> Option<i32> {
+        self.0.scoremode
+    }
+
+    /// All notes of the course, bar lines and gogo time markers included.
+    #[getter]
+    fn notes(&self) -> Vec<PyTaikoNote> {
+        self.0.notes.iter().copied().map(PyTaikoNote).collect()
+    }
+
+    /// `(time, bpm)` pairs.
+    #[getter]
+    fn bpms(&self) -> Vec<(f64, f32)> {
+        self.0.bpms.clone()
+    }
+
+    fn note_count(&self) -> usize {
+        self.0.note_count()
+    }
+
+    fn bpm_range(&self) -> Option<(f32, f32)> {
+        self.0.bpm_range()
+    }
+
+    fn drumroll_time(&self) -> f64 {
+        self.0.drumroll_time()
+    }
+
+    fn length(&self) -> f64 {
+        self.0.length()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TJACourse(course={}, level={:?}, notes={})",
+            self.0.course,
+            self.0.level,
+            self.0.note_count()
+        )
+    }
+}
+
+#[pyclass(name = "TaikoNote", module = "rhythm_rs")]
+#[derive(Clone)]
+pub struct PyTaikoNote(pub TaikoNote);
+
+#[pymethods]
+impl PyTaikoNote {
+    /// in seconds
+    #[getter]
+    fn start(&self) -> f64 {
+        self.0.start
+    }
+
+    /// in seconds
+    #[getter]
+    fn duration(&self) -> f64 {
+        self.0.duration
+    }
+
+    #[getter]
+    fn volume(&self) -> u16 {
+        self.0.volume
+    }
+
+    /// "Don", "Kat", "Both", "Invisible" or "Unknown".
+    #[getter]
+    fn variant(&self) -> String {
+        format!("{:?}", self.0.variant)
+    }
+
+    /// "Small", "Big", "SmallCombo", "BigCombo", "Balloon", "Yam", "GogoStart", "GogoEnd" or "BarLine".
+    #[getter]
+    fn note_type(&self) -> String {
+        format!("{:?}", self.0.note_type)
+    }
+
+    #[getter]
+    fn speed(&self) -> f32 {
+        self.0.speed
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TaikoNote(start={}, variant={:?}, type={:?})",
+            self.0.start, self.0.variant, self.0.note_type
+        )
+    }
+}
@@ -0,0 +1,122 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rhythm_core::{transform, Note};
+use taiko_core::{DefaultTaikoEngine, Final, GameSource, Hit, InputState, TaikoEngine};
+use tja::TJACourse;
+
+use crate::chart::PyTJACourse;
+
+#[pyclass(name = "Final", module = "rhythm_rs", get_all)]
+#[derive(Clone)]
+pub struct PyFinal {
+    pub score: u32,
+    pub max_combo: u32,
+    pub gauge: f64,
+    pub greats: u32,
+    pub goods: u32,
+    pub misses: u32,
+    pub max_hit: u32,
+    pub unpopped: u32,
+    pub passed: bool,
+}
+
+impl From<Final> for PyFinal {
+    fn from(value: Final) -> Self {
+        Self {
+            score: value.score,
+            max_combo: value.max_combo,
+            gauge: value.gauge,
+            greats: value.greats,
+            goods: value.goods,
+            misses: value.misses,
+            max_hit: value.max_hit,
+            unpopped: value.unpopped,
+            passed: value.passed,
+        }
+    }
+}
+
+#[pymethods]
+impl PyFinal {
+    fn __repr__(&self) -> String {
+        format!(
+            "Final(score={}, max_combo={}, greats={}, goods={}, misses={}, passed={})",
+            self.score, self.max_combo, self.greats, self.goods, self.misses, self.passed
+        )
+    }
+}
+
+/// Plays the course with the hits, which are `(time, hit)` pairs in seconds, and returns the result.
+/// The notes are moved by `-offset`, so passing the `OFFSET` of the TJA puts them on the time of the music.
+pub fn replay(course: &TJACourse, hits: &[(f64, Hit)], offset: f64) -> Final {
+    let mut source = GameSource::from(course);
+    transform::offset(&mut source.notes, -offset);
+    let end = source
+        .notes
+        .iter()
+        .map(|note| note.start() + note.duration())
+        .fold(0.0, f64::max);
+
+    let mut engine = DefaultTaikoEngine::new(source);
+
+    // the engine judges every hit at its own time, and lets every remaining note pass
+    let time = hits.iter().map(|(time, _)| *time).fold(end + 1.0, f64::max);
+    engine.forward(InputState {
+        time,
+        hits: hits.to_vec(),
+    });
+
+    engine.finalize()
+}
+
+/// Plays the course with `hits`, a list of `(time, hit)` pairs, and returns the `Final` result.
+/// A hit is `"don"` or `"kat"`, or `"don_left"`, `"don_right"`, `"kat_left"` or `"kat_right"` with the side.
+/// Raises `ValueError` on an unknown hit or a non-finite time.
+#[pyfunction]
+#[pyo3(signature = (course, hits, offset = 0.0))]
+pub fn play(course: &PyTJACourse, hits: Vec<(f64, String)>, offset: f64) -> PyResult<PyFinal> {
+    if hits.iter().any(|(time, _)| !time.is_finite()) {
+        return Err(PyValueError::new_err("hit time is not finite"));
+    }
+    let hits = hits
+        .into_iter()
+        .map(|(time, hit)| match hit.to_lowercase().as_str() {
+            "don" => Ok((time, Hit::Don)),
+            "kat" => Ok((time, Hit::Kat)),
+            "don_left" => Ok((time, Hit::DonLeft)),
+            "don_right" => Ok((time, Hit::DonRight)),
+            "kat_left" => Ok((time, Hit::KatLeft)),
+            "kat_right" => Ok((time, Hit::KatRight)),
+            _ => Err(PyValueError::new_err(format!("unknown hit: {}", hit))),
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(replay(&course.0, &hits, offset).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tja::TJAParser;
+
+    #[test]
+    fn test_replay() {
+        let tja = TJAParser::new()
+            .parse("BPM:120\nOFFSET:-1\nCOURSE:Oni\nLEVEL:8\n#START\n1212,\n#END\n")
+            .unwrap();
+        let course = &tja.courses[0];
+
+        let hits = [
+            (1.5, Hit::Kat),
+            (1.0, Hit::Don),
+            (2.0, Hit::Don),
+            (2.5, Hit::Don),
+        ];
+        let result = replay(course, &hits, -1.0);
+        assert_eq!((result.greats, result.misses), (3, 1));
+        assert_eq!(result.max_combo, 3);
+
+        let result = replay(course, &[], -1.0);
+        assert_eq!(result.misses, 4);
+    }
+}
@@ -0,0 +1,21 @@
+//! Python bindings of the TJA parser and the taiko game core, built with maturin as the `rhythm_rs` module.
+
+use pyo3::prelude::*;
+
+pub mod chart;
+pub mod engine;
+
+pub use chart::*;
+pub use engine::*;
+
+#[pymodule]
+fn rhythm_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyTJAParser>()?;
+    m.add_class::<PyTJA>()?;
+    m.add_class::<PyTJAHeader>()?;
+    m.add_class::<PyTJACourse>()?;
+    m.add_class::<PyTaikoNote>()?;
+    m.add_class::<PyFinal>()?;
+    m.add_function(wrap_pyfunction!(play, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+import pytest
+
+import rhythm_rs
+
+TJA = "BPM:120\nOFFSET:-1\nCOURSE:Oni\nLEVEL:8\n#START\n1212,\n#END\n"
+
+
+def test_parse():
+    tja = rhythm_rs.TJAParser().parse(TJA)
+    assert tja.header.bpm == 120.0
+    course = tja.courses[0]
+    assert course.course == 3
+    assert course.note_count() == 4
+    assert [note.variant for note in course.notes[1:]] == ["Don", "Kat", "Don", "Kat"]
+
+
+def test_play():
+    tja = rhythm_rs.TJAParser().parse(TJA)
+    course = tja.courses[0]
+    hits = [(1.0, "don"), (1.5, "kat"), (2.0, "don"), (2.5, "kat")]
+    result = rhythm_rs.play(course, hits, offset=tja.header.offset)
+    assert result.greats == 4
+    assert result.passed
+
+    with pytest.raises(ValueError):
+        rhythm_rs.play(course, [(1.0, "ka")])
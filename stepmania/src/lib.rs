@@ -0,0 +1,7 @@
+pub mod note;
+pub mod parser;
+pub mod sm;
+
+pub use note::*;
+pub use parser::*;
+pub use sm::*;
@@ -0,0 +1,109 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use rhythm_core::{Note, SimpleNote};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StepNoteType {
+    /// `1`
+    Tap,
+    /// `2`, ended by a `3`.
+    Hold,
+    /// `4`, ended by a `3`.
+    Roll,
+    /// `M`, must not be hit.
+    Mine,
+    /// `L`, hit on release.
+    Lift,
+    /// `F`, neither judged nor scored.
+    Fake,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StepNote {
+    /// in seconds
+    pub start: f64,
+    /// in seconds, only holds and rolls have a duration
+    pub duration: f64,
+    /// The beat the note is placed on.
+    pub beat: f64,
+    /// 1 for taps and lifts, 2 for holds and rolls (see [`Note::is_hold`]), 0 for mines and fakes.
+    pub volume: u16,
+    /// The lane (column) of the note.
+    pub lane: u16,
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub note_type: StepNoteType,
+}
+
+impl StepNote {
+    /// Converts the note to a [`SimpleNote`] whose variant is the lane.
+    /// Holds and rolls become hold notes; mines and fakes are not playable and return `None`.
+    pub fn to_simple(&self) -> Option<SimpleNote> {
+        match self.note_type {
+            StepNoteType::Tap | StepNoteType::Lift => {
+                Some(SimpleNote::new(self.start, 0.0, 1u16, self.lane))
+            }
+            StepNoteType::Hold | StepNoteType::Roll => {
+                Some(SimpleNote::new_hold(self.start, self.duration, self.lane))
+            }
+            StepNoteType::Mine | StepNoteType::Fake => None,
+        }
+    }
+}
+
+impl Note for StepNote {
+    fn start(&self) -> f64 {
+        self.start
+    }
+
+    fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    fn volume(&self) -> u16 {
+        self.volume
+    }
+
+    #[allow(refining_impl_trait)]
+    fn variant(&self) -> u16 {
+        self.lane
+    }
+
+    fn set_start(&mut self, start: f64) {
+        self.start = start;
+    }
+
+    fn set_duration(&mut self, duration: f64) {
+        self.duration = duration;
+    }
+
+    fn set_volume(&mut self, volume: u16) {
+        self.volume = volume;
+    }
+
+    fn set_variant(&mut self, variant: impl Into<u16>) {
+        self.lane = variant.into();
+    }
+
+    fn is_hold(&self) -> bool {
+        matches!(self.note_type, StepNoteType::Hold | StepNoteType::Roll)
+    }
+}
+
+impl Eq for StepNote {}
+
+impl Ord for StepNote {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.start
+            .total_cmp(&other.start)
+            .then(self.lane.cmp(&other.lane))
+    }
+}
+
+impl PartialOrd for StepNote {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(Ord::cmp(self, other))
+    }
+}
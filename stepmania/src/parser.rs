@@ -0,0 +1,408 @@
+use crate::note::{StepNote, StepNoteType};
+use crate::sm::{SMChart, SMHeader, TimingData, SM};
+
+/// Parses StepMania `.sm` and `.ssc` files.
+pub struct SMParser {}
+
+impl Default for SMParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SMParser {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn parse(&self, content: impl AsRef<str>) -> Result<SM, &'static str> {
+        let mut sm = SM {
+            header: SMHeader::new(),
+            charts: Vec::new(),
+        };
+
+        // charts of .ssc files are opened by #NOTEDATA and closed by #NOTES
+        let mut chart: Option<SMChart> = None;
+
+        for (key, value) in tags(content.as_ref()) {
+            let key = key.to_uppercase();
+            let value = value.as_str();
+
+            if let Some(chart) = chart.as_mut() {
+                match key.as_str() {
+                    "STEPSTYPE" => chart.steps_type = value.to_string(),
+                    "DESCRIPTION" => chart.description = value.to_string(),
+                    "DIFFICULTY" => chart.difficulty = value.to_string(),
+                    "METER" => chart.meter = value.parse().unwrap_or(0),
+                    "OFFSET" => chart.timing.offset = value.parse().unwrap_or(0.0),
+                    "BPMS" => chart.timing.bpms = parse_bpms(value)?,
+                    "STOPS" | "FREEZES" => chart.timing.stops = parse_pairs(value)?,
+                    "DELAYS" => chart.timing.delays = parse_pairs(value)?,
+                    _ => {}
+                }
+                if key != "NOTES" {
+                    continue;
+                }
+            }
+
+            match key.as_str() {
+                "TITLE" => sm.header.title = Some(value.to_string()),
+                "SUBTITLE" => sm.header.subtitle = Some(value.to_string()),
+                "ARTIST" => sm.header.artist = Some(value.to_string()),
+                "GENRE" => sm.header.genre = Some(value.to_string()),
+                "CREDIT" => sm.header.credit = Some(value.to_string()),
+                "MUSIC" => sm.header.music = Some(value.to_string()),
+                "SAMPLESTART" => sm.header.sample_start = value.parse().ok(),
+                "SAMPLELENGTH" => sm.header.sample_length = value.parse().ok(),
+                "OFFSET" => sm.header.timing.offset = value.parse().unwrap_or(0.0),
+                "BPMS" => sm.header.timing.bpms = parse_bpms(value)?,
+                "STOPS" | "FREEZES" => sm.header.timing.stops = parse_pairs(value)?,
+                "DELAYS" => sm.header.timing.delays = parse_pairs(value)?,
+                "NOTEDATA" => chart = Some(new_chart(&sm.header.timing)),
+                "NOTES" | "NOTES2" => {
+                    let (mut current, data) = match chart.take() {
+                        Some(chart) => (chart, value),
+                        None => {
+                            // .sm: type:description:difficulty:meter:radar:data
+                            let fields = value.splitn(6, ':').collect::<Vec<_>>();
+                            if fields.len() != 6 {
+                                return Err("invalid #NOTES");
+                            }
+                            let mut chart = new_chart(&sm.header.timing);
+                            chart.steps_type = fields[0].trim().to_string();
+                            chart.description = fields[1].trim().to_string();
+                            chart.difficulty = fields[2].trim().to_string();
+                            chart.meter = fields[3].trim().parse().unwrap_or(0);
+                            (chart, fields[5])
+                        }
+                    };
+
+                    if current.timing.bpms.is_empty() {
+                        return Err("missing #BPMS");
+                    }
+                    let (keys, notes) = parse_notes(data, &current.timing)?;
+                    current.keys = keys;
+                    current.notes = notes;
+                    sm.charts.push(current);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(sm)
+    }
+}
+
+fn new_chart(timing: &TimingData) -> SMChart {
+    SMChart {
+        steps_type: String::new(),
+        description: String::new(),
+        difficulty: String::new(),
+        meter: 0,
+        keys: 0,
+        timing: timing.clone(),
+        notes: Vec::new(),
+    }
+}
+
+/// Splits the content into `#KEY:VALUE;` pairs, with comments removed.
+/// A missing `;` is tolerated when the next tag starts on a new line.
+fn tags(content: &str) -> Vec<(String, String)> {
+    let mut tags = Vec::new();
+    let mut current: Option<(String, String)> = None;
+
+    for mut line in content.lines() {
+        if let Some(pair) = line.split_once("//") {
+            line = pair.0;
+        }
+
+        let mut rest = line;
+        if rest.trim_start().starts_with('#') {
+            if let Some((key, value)) = current.take() {
+                tags.push((key, value.trim().to_string()));
+            }
+        }
+
+        while !rest.is_empty() {
+            match current.as_mut() {
+                Some((_, value)) => match rest.split_once(';') {
+                    Some((end, remain)) => {
+                        value.push_str(end);
+                        let (key, value) = current.take().unwrap();
+                        tags.push((key, value.trim().to_string()));
+                        rest = remain;
+                    }
+                    None => {
+                        value.push_str(rest);
+                        value.push('\n');
+                        rest = "";
+                    }
+                },
+                None => match rest.split_once('#') {
+                    Some((_, tag)) => {
+                        let (key, value) = tag.split_once(':').unwrap_or((tag, ""));
+                        current = Some((key.trim().to_string(), String::new()));
+                        rest = value;
+                        if rest.is_empty() {
+                            current.as_mut().unwrap().1.push('\n');
+                        }
+                    }
+                    None => rest = "",
+                },
+            }
+        }
+    }
+
+    if let Some((key, value)) = current.take() {
+        tags.push((key, value.trim().to_string()));
+    }
+
+    tags
+}
+
+/// Parses `beat=value` lists such as `#BPMS` and `#STOPS`, sorted by beat.
+/// Non-finite beats and values (e.g. `nan`, which `f64::from_str` accepts) are rejected.
+fn parse_pairs(value: &str) -> Result<Vec<(f64, f64)>, &'static str> {
+    let mut pairs = Vec::new();
+    for pair in value.split(',') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+        let (beat, value) = pair.split_once('=').ok_or("invalid beat pair")?;
+        let beat: f64 = beat.trim().parse().map_err(|_| "invalid beat")?;
+        let value: f64 = value.trim().parse().map_err(|_| "invalid beat value")?;
+        if !beat.is_finite() {
+            return Err("invalid beat");
+        }
+        if !value.is_finite() {
+            return Err("invalid beat value");
+        }
+        pairs.push((beat, value));
+    }
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(pairs)
+}
+
+/// Parses `#BPMS`. Warps (negative BPMs) are not supported, so only positive BPMs are accepted.
+fn parse_bpms(value: &str) -> Result<Vec<(f64, f64)>, &'static str> {
+    let bpms = parse_pairs(value)?;
+    if bpms.iter().any(|(_, bpm)| *bpm <= 0.0) {
+        return Err("invalid bpm");
+    }
+    Ok(bpms)
+}
+
+/// Parses the measures of a chart, returning the number of lanes and the notes.
+fn parse_notes(data: &str, timing: &TimingData) -> Result<(u16, Vec<StepNote>), &'static str> {
+    let mut keys = 0;
+    let mut notes: Vec<StepNote> = Vec::new();
+    // index of the unfinished hold or roll of each lane
+    let mut heads: Vec<Option<usize>> = Vec::new();
+
+    // only the first player of couple/routine charts is read
+    let data = data.split('&').next().unwrap_or("");
+
+    for (measure, rows) in data.split(',').enumerate() {
+        let rows = rows
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .collect::<Vec<_>>();
+
+        for (i, row) in rows.iter().enumerate() {
+            if keys == 0 {
+                keys = row.chars().count();
+                heads = vec![None; keys];
+            } else if row.chars().count() != keys {
+                return Err("inconsistent row width");
+            }
+
+            let beat = measure as f64 * 4.0 + 4.0 * i as f64 / rows.len() as f64;
+            let time = timing.time_at(beat);
+
+            for (lane, c) in row.chars().enumerate() {
+                let (note_type, volume) = match c {
+                    '1' => (StepNoteType::Tap, 1),
+                    '2' => (StepNoteType::Hold, 2),
+                    '4' => (StepNoteType::Roll, 2),
+                    'M' | 'm' => (StepNoteType::Mine, 0),
+                    'L' | 'l' => (StepNoteType::Lift, 1),
+                    'F' | 'f' => (StepNoteType::Fake, 0),
+                    '3' => {
+                        if let Some(head) = heads[lane].take() {
+                            notes[head].duration = time - notes[head].start;
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                if note_type == StepNoteType::Hold || note_type == StepNoteType::Roll {
+                    heads[lane] = Some(notes.len());
+                }
+                notes.push(StepNote {
+                    start: time,
+                    duration: 0.0,
+                    beat,
+                    volume,
+                    lane: lane as u16,
+                    note_type,
+                });
+            }
+        }
+    }
+
+    // holds without a tail are played as taps
+    for head in heads.into_iter().flatten() {
+        notes[head].note_type = StepNoteType::Tap;
+        notes[head].volume = 1;
+    }
+
+    notes.sort();
+    Ok((keys as u16, notes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rhythm_core::Note;
+
+    const SM_FILE: &str = "
+#TITLE:Sample;
+#ARTIST:Someone;
+#OFFSET:-0.100;
+#BPMS:0.000=120.000,
+4.000=240.000;
+#STOPS:2.000=0.500;
+// a comment
+#NOTES:
+     dance-single:
+     Author:
+     Hard:
+     9:
+     0.1,0.2,0.3,0.4,0.5:
+1000
+0200
+0300
+000M
+,
+0000
+4000
+3000
+0001
+;
+";
+
+    #[test]
+    fn parse_sm() {
+        let sm = SMParser::new().parse(SM_FILE).unwrap();
+        assert_eq!(sm.header.title.as_deref(), Some("Sample"));
+        assert_eq!(sm.header.timing.bpms, vec![(0.0, 120.0), (4.0, 240.0)]);
+        assert_eq!(sm.charts.len(), 1);
+
+        let chart = &sm.charts[0];
+        assert_eq!(chart.steps_type, "dance-single");
+        assert_eq!(chart.difficulty, "Hard");
+        assert_eq!(chart.meter, 9);
+        assert_eq!(chart.keys, 4);
+
+        let summary = chart
+            .notes
+            .iter()
+            .map(|n| {
+                let ms = |t: f64| (t * 1000.0).round() as i64;
+                (ms(n.start), ms(n.duration), n.lane, n.note_type)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (100, 0, 0, StepNoteType::Tap),
+                (600, 500, 1, StepNoteType::Hold),
+                (2100, 0, 3, StepNoteType::Mine),
+                (2850, 250, 0, StepNoteType::Roll),
+                (3350, 0, 3, StepNoteType::Tap),
+            ]
+        );
+
+        let simple = chart.simple_notes();
+        assert_eq!(simple.len(), 4);
+        assert!(simple[1].is_hold());
+        assert_eq!(simple[1].variant, 1);
+    }
+
+    #[test]
+    fn parse_ssc() {
+        let ssc = "
+#VERSION:0.83;
+#TITLE:Sample
+#OFFSET:0;
+#BPMS:0=60;
+#NOTEDATA:;
+#STEPSTYPE:dance-single;
+#DIFFICULTY:Easy;
+#METER:3;
+#NOTES:
+1001
+;
+#NOTEDATA:;
+#STEPSTYPE:pump-single;
+#DIFFICULTY:Hard;
+#METER:12;
+#BPMS:0=120;
+#DELAYS:0=1;
+#NOTES:
+00000
+00100
+;
+";
+        let sm = SMParser::new().parse(ssc).unwrap();
+        assert_eq!(sm.header.title.as_deref(), Some("Sample"));
+        assert_eq!(sm.charts.len(), 2);
+
+        assert_eq!(sm.charts[0].keys, 4);
+        assert_eq!(sm.charts[0].meter, 3);
+        assert_eq!(sm.charts[0].notes.len(), 2);
+        assert_eq!(sm.charts[0].notes[1].lane, 3);
+
+        assert_eq!(sm.charts[1].keys, 5);
+        assert_eq!(sm.charts[1].timing.bpms, vec![(0.0, 120.0)]);
+        assert_eq!(sm.charts[1].notes[0].start, 2.0);
+        assert_eq!(sm.header.timing.bpms, vec![(0.0, 60.0)]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parser = SMParser::new();
+        assert_eq!(
+            parser.parse("#NOTES:dance-single:::1:0:1000;").unwrap_err(),
+            "missing #BPMS"
+        );
+        assert_eq!(
+            parser
+                .parse("#BPMS:0=60;#NOTES:dance-single:1000;")
+                .unwrap_err(),
+            "invalid #NOTES"
+        );
+        assert_eq!(
+            parser
+                .parse("#BPMS:0=60;#NOTES:dance-single::::0:1000\n10000;")
+                .unwrap_err(),
+            "inconsistent row width"
+        );
+        assert_eq!(
+            parser.parse("#BPMS:0=nan;").unwrap_err(),
+            "invalid beat value"
+        );
+        assert_eq!(
+            parser.parse("#BPMS:0=60;#STOPS:inf=1;").unwrap_err(),
+            "invalid beat"
+        );
+        assert_eq!(parser.parse("#BPMS:0=0;").unwrap_err(), "invalid bpm");
+        assert_eq!(
+            parser.parse("#BPMS:0=120,4=-120,8=120;").unwrap_err(),
+            "invalid bpm"
+        );
+    }
+}
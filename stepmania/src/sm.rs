@@ -0,0 +1,122 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use rhythm_core::SimpleNote;
+
+use crate::note::StepNote;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SM {
+    pub header: SMHeader,
+    pub charts: Vec<SMChart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SMHeader {
+    /// The title of the song.
+    pub title: Option<String>,
+    /// The subtitle of the song.
+    pub subtitle: Option<String>,
+    /// The artist of the song.
+    pub artist: Option<String>,
+    /// The genre of the song.
+    pub genre: Option<String>,
+    /// The author of the charts.
+    pub credit: Option<String>,
+    /// The music file of the song.
+    pub music: Option<String>,
+    /// The start of the preview, in seconds.
+    pub sample_start: Option<f64>,
+    /// The length of the preview, in seconds.
+    pub sample_length: Option<f64>,
+    /// The tempo data shared by all charts.
+    pub timing: TimingData,
+}
+
+impl SMHeader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SMChart {
+    /// The game mode of the chart, e.g. "dance-single".
+    pub steps_type: String,
+    pub description: String,
+    /// The difficulty name, e.g. "Beginner", "Easy", "Medium", "Hard" or "Challenge".
+    pub difficulty: String,
+    pub meter: i32,
+    /// The number of lanes.
+    pub keys: u16,
+    /// The tempo data of the chart, which is the song's unless the chart overrides it (.ssc only).
+    pub timing: TimingData,
+    pub notes: Vec<StepNote>,
+}
+
+impl SMChart {
+    /// Returns the playable notes of the chart as [`SimpleNote`]s, see [`StepNote::to_simple`].
+    pub fn simple_notes(&self) -> Vec<SimpleNote> {
+        self.notes.iter().filter_map(StepNote::to_simple).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimingData {
+    /// The time of beat 0 is `-offset` seconds.
+    pub offset: f64,
+    /// `(beat, bpm)` pairs, sorted by beat. The BPMs are positive, warps are not supported.
+    pub bpms: Vec<(f64, f64)>,
+    /// `(beat, seconds)` pairs, sorted by beat. The chart pauses after the notes on the beat.
+    pub stops: Vec<(f64, f64)>,
+    /// `(beat, seconds)` pairs, sorted by beat. The chart pauses before the notes on the beat.
+    pub delays: Vec<(f64, f64)>,
+}
+
+impl TimingData {
+    /// Returns the BPM at the given beat.
+    pub fn bpm_at(&self, beat: f64) -> f64 {
+        self.bpms
+            .iter()
+            .take_while(|(b, _)| *b <= beat)
+            .last()
+            .or(self.bpms.first())
+            .map(|(_, bpm)| *bpm)
+            .unwrap_or(60.0)
+    }
+
+    /// Returns the time of the given beat, in seconds.
+    pub fn time_at(&self, beat: f64) -> f64 {
+        let mut time = 0.0;
+        let mut last_beat = 0.0;
+        let mut bpm = self.bpms.first().map(|(_, bpm)| *bpm).unwrap_or(60.0);
+        for (b, v) in self.bpms.iter().skip(1) {
+            if *b >= beat {
+                break;
+            }
+            time += (b - last_beat) * 60.0 / bpm;
+            last_beat = *b;
+            bpm = *v;
+        }
+        time += (beat - last_beat) * 60.0 / bpm;
+
+        let stops = self
+            .stops
+            .iter()
+            .filter(|(b, _)| *b < beat)
+            .map(|(_, s)| s)
+            .sum::<f64>();
+        let delays = self
+            .delays
+            .iter()
+            .filter(|(b, _)| *b <= beat)
+            .map(|(_, s)| s)
+            .sum::<f64>();
+
+        time + stops + delays - self.offset
+    }
+}
@@ -113,5 +113,36 @@ pub const GUAGE_FULL_THRESHOLD: [[f64; 11]; 5] = [
 pub const RANGE_GREAT: f64 = 0.03;
 pub const RANGE_OK: f64 = 0.08;
 pub const RANGE_MISS: f64 = 0.11;
+/// How long (in seconds) after the first hit on a big note the second hit counts.
+pub const BIG_HIT_WINDOW: f64 = 0.05;
 
 pub const COURSE_TYPE: [&str; 5] = ["Easy", "Normal", "Hard", "Oni", "Ura"];
+
+/// The name of the `COURSE:` in [`COURSE_TYPE`], or "Unknown" for the ones it does not have (e.g. Dan and Tower).
+///
+/// ```
+/// use taiko_core::constant::course_name;
+///
+/// assert_eq!(course_name(3), "Oni");
+/// assert_eq!(course_name(6), "Unknown");
+/// assert_eq!(course_name(-1), "Unknown");
+/// ```
+pub fn course_name(course: i32) -> &'static str {
+    usize::try_from(course)
+        .ok()
+        .and_then(|course| COURSE_TYPE.get(course))
+        .copied()
+        .unwrap_or("Unknown")
+}
+
+/// The bonus for every [`COMBO_BONUS_INTERVAL`] combo, before shin-uchi.
+pub const COMBO_BONUS: u32 = 10000;
+pub const COMBO_BONUS_INTERVAL: u32 = 100;
+/// The score of a hit on a small drumroll before shin-uchi. Big drumrolls are worth twice as much.
+pub const ROLL_HIT_SCORE: u32 = 300;
+/// The score of a hit on a balloon before shin-uchi.
+pub const BALLOON_HIT_SCORE: u32 = 300;
+/// The bonus for popping a balloon before shin-uchi.
+pub const BALLOON_POP_SCORE: u32 = 5000;
+/// The score of a hit on any drumroll or balloon in shin-uchi.
+pub const SHIN_UCHI_HIT_SCORE: u32 = 100;
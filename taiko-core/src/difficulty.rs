@@ -0,0 +1,225 @@
+//! An objective difficulty model (star rating) of taiko courses.
+//!
+//! Every skill is measured as the peak rate of its events over a sliding window of [`WINDOW`] seconds,
+//! so a short hard section counts more than a long easy one.
+//! The skills are combined into a star rating roughly on the scale of `LEVEL:` (1 to 10).
+
+use serde::{Deserialize, Serialize};
+use tja::{TaikoNote, TaikoNoteType, TaikoNoteVariant};
+
+/// The length of the sliding window, in seconds.
+pub const WINDOW: f64 = 4.0;
+
+/// Intervals longer than this (in seconds) are rests, and do not form patterns.
+const REST: f64 = 1.0;
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct Difficulty {
+    /// Changes between note intervals per second, weighted by how irregular they are.
+    pub rhythm: f64,
+    /// Don/kat switches per second.
+    pub colour: f64,
+    /// Notes per second.
+    pub stamina: f64,
+    /// Scroll and BPM changes per second, weighted by how much the speed changes.
+    pub reading: f64,
+    /// The combined rating.
+    pub stars: f64,
+}
+
+/// The weights of the skills in the star rating.
+const WEIGHT_RHYTHM: f64 = 0.6;
+const WEIGHT_COLOUR: f64 = 0.5;
+const WEIGHT_STAMINA: f64 = 0.45;
+const WEIGHT_READING: f64 = 1.0;
+/// Maps the square root of the weighted skills to stars, fitted against the `LEVEL:` of the sample charts.
+const STAR_SCALE: f64 = 2.6;
+
+impl Difficulty {
+    /// Whether the star rating is more than `tolerance` away from the `LEVEL:` given by the charter.
+    pub fn is_mislabelled(&self, level: i32, tolerance: f64) -> bool {
+        (self.stars - level as f64).abs() > tolerance
+    }
+}
+
+/// Returns the largest sum of weights in a `window` seconds long span, divided by the window.
+fn peak_rate(events: &[(f64, f64)], window: f64) -> f64 {
+    let mut peak: f64 = 0.0;
+    let mut sum = 0.0;
+    let mut start = 0;
+    for (time, weight) in events.iter() {
+        sum += weight;
+        while events[start].0 <= time - window {
+            sum -= events[start].1;
+            start += 1;
+        }
+        peak = peak.max(sum);
+    }
+    peak / window
+}
+
+/// The cost of going from the interval `previous` to `current`:
+/// 0 for the same interval, more for bigger changes, and extra for ratios that are not powers of two (e.g. triplets).
+fn rhythm_cost(previous: f64, current: f64) -> f64 {
+    let ratio = (current / previous).log2();
+    if ratio.abs() < 0.05 {
+        return 0.0;
+    }
+    let irregular = if (ratio - ratio.round()).abs() < 0.05 {
+        0.0
+    } else {
+        1.0
+    };
+    ratio.abs().min(2.0) * 0.5 + irregular
+}
+
+/// Estimates the difficulty of a course from its notes.
+/// Only dons and kats are rated; drumrolls, balloons and events are ignored.
+pub fn estimate(notes: &[TaikoNote]) -> Difficulty {
+    let mut hits = notes
+        .iter()
+        .filter(|note| {
+            (note.variant == TaikoNoteVariant::Don || note.variant == TaikoNoteVariant::Kat)
+                && (note.note_type == TaikoNoteType::Small || note.note_type == TaikoNoteType::Big)
+        })
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let stamina = hits
+        .iter()
+        .map(|note| (note.start, 1.0))
+        .collect::<Vec<_>>();
+
+    let mut colour = vec![];
+    let mut rhythm = vec![];
+    let mut reading = vec![];
+    for i in 1..hits.len() {
+        let (previous, note) = (hits[i - 1], hits[i]);
+        let interval = note.start - previous.start;
+
+        if note.variant != previous.variant && interval < REST {
+            colour.push((note.start, 1.0));
+        }
+
+        if i >= 2 {
+            let last = previous.start - hits[i - 2].start;
+            if interval < REST && last < REST && last > 0.0 && interval > 0.0 {
+                rhythm.push((note.start, rhythm_cost(last, interval)));
+            }
+        }
+
+        if previous.speed > 0.0 && note.speed > 0.0 && note.speed != previous.speed {
+            reading.push((
+                note.start,
+                (note.speed / previous.speed).log2().abs() as f64,
+            ));
+        }
+    }
+
+    let rhythm = peak_rate(&rhythm, WINDOW);
+    let colour = peak_rate(&colour, WINDOW);
+    let stamina = peak_rate(&stamina, WINDOW);
+    let reading = peak_rate(&reading, WINDOW);
+
+    let strain = WEIGHT_RHYTHM * rhythm
+        + WEIGHT_COLOUR * colour
+        + WEIGHT_STAMINA * stamina
+        + WEIGHT_READING * reading;
+    let stars = STAR_SCALE * strain.sqrt();
+
+    Difficulty {
+        rhythm,
+        colour,
+        stamina,
+        reading,
+        stars,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tja::TJAParser;
+
+    fn note(start: f64, variant: TaikoNoteVariant, speed: f32) -> TaikoNote {
+        TaikoNote {
+            start,
+            duration: 0.0,
+            volume: 1,
+            variant,
+            note_type: TaikoNoteType::Small,
+            speed,
+        }
+    }
+
+    #[test]
+    fn test_skills() {
+        // 8 notes per second for 4 seconds
+        let mono = (0..32)
+            .map(|i| note(i as f64 / 8.0, TaikoNoteVariant::Don, 120.0))
+            .collect::<Vec<_>>();
+        let mono = estimate(&mono);
+        assert_eq!(mono.stamina, 8.0);
+        assert_eq!((mono.colour, mono.rhythm, mono.reading), (0.0, 0.0, 0.0));
+
+        let alternating = (0..32)
+            .map(|i| {
+                let variant = if i % 2 == 0 {
+                    TaikoNoteVariant::Don
+                } else {
+                    TaikoNoteVariant::Kat
+                };
+                note(i as f64 / 8.0, variant, if i < 16 { 120.0 } else { 240.0 })
+            })
+            .collect::<Vec<_>>();
+        let alternating = estimate(&alternating);
+        assert_eq!(alternating.colour, 31.0 / WINDOW);
+        assert_eq!(alternating.reading, 1.0 / WINDOW);
+        assert!(alternating.stars > mono.stars);
+
+        // triplets after straight notes
+        let mut triplets = (0..8)
+            .map(|i| note(i as f64 / 4.0, TaikoNoteVariant::Don, 120.0))
+            .collect::<Vec<_>>();
+        triplets.extend((1..=6).map(|i| note(1.75 + i as f64 / 6.0, TaikoNoteVariant::Don, 120.0)));
+        assert!(estimate(&triplets).rhythm > 0.0);
+
+        assert_eq!(estimate(&[]).stars, 0.0);
+    }
+
+    #[test]
+    fn test_samples() {
+        let raw = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../tja/samples/Nosferatu.tja"
+        ))
+        .unwrap();
+        let tja = TJAParser::new().parse(raw).unwrap();
+
+        let mut courses = tja.courses.iter().collect::<Vec<_>>();
+        courses.sort_by_key(|course| course.course);
+        let stars = courses
+            .iter()
+            .map(|course| estimate(&course.notes).stars)
+            .collect::<Vec<_>>();
+        assert!(stars.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // STAR_SCALE is fitted so that every course of every sample is within a level of its LEVEL:
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../tja/samples");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let raw = std::fs::read_to_string(&path).unwrap();
+            for course in TJAParser::new().parse(raw).unwrap().courses {
+                let difficulty = estimate(&course.notes);
+                assert!(
+                    !difficulty.is_mislabelled(course.level.unwrap(), 1.0),
+                    "{} course {}: {} stars for level {:?}",
+                    path.display(),
+                    course.course,
+                    difficulty.stars,
+                    course.level
+                );
+            }
+        }
+    }
+}
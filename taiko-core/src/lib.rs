@@ -1,4 +1,9 @@
 pub mod constant;
+pub mod difficulty;
+pub mod pattern;
+pub mod ruleset;
+pub mod score;
+pub mod sticking;
 pub mod taiko;
 
 pub use taiko::*;
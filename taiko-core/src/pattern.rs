@@ -0,0 +1,203 @@
+//! Recognition of common patterns in the note stream of a course, for highlighting tricky sections and building drills.
+
+use serde::{Deserialize, Serialize};
+use tja::{TaikoNote, TaikoNoteType, TaikoNoteVariant};
+
+/// Notes closer than this (in seconds) are played as one pattern.
+pub const MAX_INTERVAL: f64 = 0.25;
+
+/// Intervals within this ratio of each other are the same interval.
+const TOLERANCE: f64 = 0.05;
+
+/// The least number of notes of alternating runs and kat runs.
+const MIN_RUN: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum PatternKind {
+    /// Two notes with a gap before and after.
+    Double,
+    /// Three notes with a gap before and after, e.g. don-don-kat.
+    Triplet,
+    /// Four or more evenly spaced notes with a gap before and after, e.g. the 5, 7 and 9 note streams.
+    Stream,
+    /// Notes switching between don and kat on every note.
+    Alternating,
+    /// Consecutive kats.
+    KatRun,
+    /// The scroll speed (BPM or scroll) changes between two notes.
+    SpeedChange,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    /// The start of the first note, in seconds.
+    pub start: f64,
+    /// The start of the last note, in seconds.
+    pub end: f64,
+    /// The indices of the notes in the course.
+    pub notes: Vec<usize>,
+}
+
+impl Pattern {
+    /// Returns the colours of the notes, `d` for don and `k` for kat, in upper case for big notes.
+    pub fn colours(&self, notes: &[TaikoNote]) -> String {
+        self.notes
+            .iter()
+            .map(|&i| {
+                let c = if notes[i].variant == TaikoNoteVariant::Don {
+                    'd'
+                } else {
+                    'k'
+                };
+                if notes[i].note_type == TaikoNoteType::Big {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+}
+
+fn same_interval(a: f64, b: f64) -> bool {
+    (a - b).abs() <= a.max(b) * TOLERANCE
+}
+
+/// Finds the patterns in the notes of a course. Only dons and kats take part in patterns.
+/// The patterns are ordered by kind, then by start time.
+pub fn find_patterns(notes: &[TaikoNote]) -> Vec<Pattern> {
+    let mut hits = (0..notes.len())
+        .filter(|&i| {
+            (notes[i].variant == TaikoNoteVariant::Don || notes[i].variant == TaikoNoteVariant::Kat)
+                && (notes[i].note_type == TaikoNoteType::Small
+                    || notes[i].note_type == TaikoNoteType::Big)
+        })
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| notes[*a].start.total_cmp(&notes[*b].start));
+
+    let pattern = |kind, run: &[usize]| Pattern {
+        kind,
+        start: notes[run[0]].start,
+        end: notes[run[run.len() - 1]].start,
+        notes: run.to_vec(),
+    };
+    let interval = |k: usize| notes[hits[k]].start - notes[hits[k - 1]].start;
+
+    let mut patterns = vec![];
+
+    // groups of evenly spaced notes
+    let mut first = 0;
+    for k in 1..=hits.len() {
+        let continues = k < hits.len()
+            && interval(k) <= MAX_INTERVAL
+            && (k - first < 2 || same_interval(interval(k), interval(k - 1)));
+        if continues {
+            continue;
+        }
+        let kind = match k - first {
+            2 => Some(PatternKind::Double),
+            3 => Some(PatternKind::Triplet),
+            n if n >= 4 => Some(PatternKind::Stream),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            // a group is only a pattern if the gap before it is longer than its interval
+            if first == 0 || interval(first) > interval(first + 1) * (1.0 + TOLERANCE) {
+                patterns.push(pattern(kind, &hits[first..k]));
+            }
+        }
+        first = k;
+    }
+
+    // runs of alternating colours and of kats
+    for (kind, continues) in [
+        (
+            PatternKind::Alternating,
+            (|a: &TaikoNote, b: &TaikoNote| a.variant != b.variant) as fn(&_, &_) -> bool,
+        ),
+        (PatternKind::KatRun, |a, b| {
+            a.variant == TaikoNoteVariant::Kat && b.variant == TaikoNoteVariant::Kat
+        }),
+    ] {
+        let mut first = 0;
+        for k in 1..=hits.len() {
+            if k < hits.len()
+                && interval(k) <= MAX_INTERVAL
+                && continues(&notes[hits[k - 1]], &notes[hits[k]])
+            {
+                continue;
+            }
+            if k - first >= MIN_RUN {
+                patterns.push(pattern(kind, &hits[first..k]));
+            }
+            first = k;
+        }
+    }
+
+    for k in 1..hits.len() {
+        if notes[hits[k]].speed != notes[hits[k - 1]].speed {
+            patterns.push(pattern(PatternKind::SpeedChange, &hits[k - 1..=k]));
+        }
+    }
+
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(start: f64, variant: TaikoNoteVariant, speed: f32) -> TaikoNote {
+        TaikoNote {
+            start,
+            duration: 0.0,
+            volume: 1,
+            variant,
+            note_type: TaikoNoteType::Small,
+            speed,
+        }
+    }
+
+    #[test]
+    fn test_find_patterns() {
+        use TaikoNoteVariant::{Don as D, Kat as K};
+
+        let mut notes = vec![];
+        let mut push = |start: f64, colours: &[TaikoNoteVariant], step: f64, speed: f32| {
+            for (i, variant) in colours.iter().enumerate() {
+                notes.push(note(start + i as f64 * step, *variant, speed));
+            }
+        };
+        push(0.0, &[D, K], 0.125, 120.0);
+        push(1.0, &[D, D, K], 0.125, 120.0);
+        push(2.0, &[D, K, D, K, D], 0.125, 120.0);
+        push(3.0, &[K, K, K, K, D, K, K], 0.125, 240.0);
+        push(5.0, &[D], 0.0, 240.0);
+        // a bar line does not split patterns
+        notes.push(TaikoNote {
+            variant: TaikoNoteVariant::Invisible,
+            note_type: TaikoNoteType::BarLine,
+            ..note(3.25, D, 240.0)
+        });
+
+        let patterns = find_patterns(&notes);
+        let found = patterns
+            .iter()
+            .map(|p| (p.kind, p.start, p.colours(&notes)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                (PatternKind::Double, 0.0, "dk".to_string()),
+                (PatternKind::Triplet, 1.0, "ddk".to_string()),
+                (PatternKind::Stream, 2.0, "dkdkd".to_string()),
+                (PatternKind::Stream, 3.0, "kkkkdkk".to_string()),
+                (PatternKind::Alternating, 2.0, "dkdkd".to_string()),
+                (PatternKind::KatRun, 3.0, "kkkk".to_string()),
+                (PatternKind::SpeedChange, 2.5, "dk".to_string()),
+            ]
+        );
+        assert_eq!(patterns[3].end, 3.75);
+    }
+}
@@ -0,0 +1,448 @@
+//! Rulesets: the judgement windows, gauge tables and scoring rules the engine plays by.
+//!
+//! With the `ruleset-files` feature, rulesets can be loaded from TOML or JSON (see [`Ruleset::from_toml`]).
+//! Missing fields are taken from the default ([`Ruleset::tjaplayer3`]), so a file only needs the values it changes:
+//! a single window of a single difficulty, or the gauge of a single level.
+
+use rhythm_core::JudgeWindows;
+use serde::{Deserialize, Serialize};
+
+use crate::constant::{
+    GUAGE_FULL_THRESHOLD, GUAGE_MISS_FACTOR, GUAGE_PASS_THRESHOLD, RANGE_GREAT, RANGE_MISS,
+    RANGE_OK,
+};
+use crate::score::ScoreMode;
+use crate::Judgement;
+
+/// A value for each difficulty.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerDifficulty<T> {
+    pub easy: T,
+    pub normal: T,
+    pub hard: T,
+    pub oni: T,
+    pub edit: T,
+}
+
+impl<T> PerDifficulty<T> {
+    /// The value of the difficulty. Unknown difficulties (e.g. Dan and Tower) use the one of Edit.
+    pub fn get(&self, difficulty: u8) -> &T {
+        match difficulty {
+            0 => &self.easy,
+            1 => &self.normal,
+            2 => &self.hard,
+            3 => &self.oni,
+            _ => &self.edit,
+        }
+    }
+
+    /// The values with the names of their difficulties.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &T)> {
+        [
+            ("easy", &self.easy),
+            ("normal", &self.normal),
+            ("hard", &self.hard),
+            ("oni", &self.oni),
+            ("edit", &self.edit),
+        ]
+        .into_iter()
+    }
+}
+
+/// The half widths of the judgement windows, in seconds.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JudgeRanges {
+    pub great: f64,
+    pub ok: f64,
+    pub miss: f64,
+}
+
+impl Default for JudgeRanges {
+    fn default() -> Self {
+        Self {
+            great: RANGE_GREAT,
+            ok: RANGE_OK,
+            miss: RANGE_MISS,
+        }
+    }
+}
+
+impl JudgeRanges {
+    pub fn windows(&self) -> JudgeWindows<Judgement> {
+        JudgeWindows::new()
+            .symmetric(Judgement::Great, self.great)
+            .symmetric(Judgement::Ok, self.ok)
+            .symmetric(Judgement::Miss, self.miss)
+    }
+}
+
+/// The judgement windows of Easy and Normal on the arcade.
+const ARCADE_EASY: JudgeRanges = JudgeRanges {
+    great: 0.0417,
+    ok: 0.1083,
+    miss: 0.125,
+};
+/// The judgement windows of Hard, Oni and Edit on the arcade.
+const ARCADE_HARD: JudgeRanges = JudgeRanges {
+    great: 0.025,
+    ok: 0.075,
+    miss: 0.1083,
+};
+
+/// The gauge rates of a course, looked up by [`Ruleset::gauge`].
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct GaugeRule {
+    pub miss_factor: f64,
+    pub pass_threshold: f64,
+    pub full_threshold: f64,
+}
+
+impl GaugeRule {
+    /// The gauge needed to pass, as a fraction of the full gauge.
+    pub fn pass(&self) -> f64 {
+        self.pass_threshold / self.full_threshold
+    }
+}
+
+/// The gauge rates of a level, in place of the ones of the gauge tables.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LevelGauge {
+    pub level: u8,
+    #[serde(flatten)]
+    pub rule: GaugeRule,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ruleset {
+    /// The judgement windows of each difficulty.
+    pub windows: PerDifficulty<JudgeRanges>,
+    /// The gauge rates of the levels that differ from the gauge tables (see [`GUAGE_MISS_FACTOR`]).
+    pub gauge: PerDifficulty<Vec<LevelGauge>>,
+    /// Overrides the `SCOREMODE:` of the charts.
+    pub score_mode: Option<ScoreMode>,
+    /// The most hits per second counted on a drumroll or balloon. Faster hits are ignored.
+    pub roll_hit_cap: Option<f64>,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::tjaplayer3()
+    }
+}
+
+impl Ruleset {
+    /// The rules of the current arcade (Nijiiro): narrower windows on Hard and Oni, and always shin-uchi scoring.
+    pub fn nijiiro() -> Self {
+        Self {
+            windows: PerDifficulty {
+                easy: ARCADE_EASY,
+                normal: ARCADE_EASY,
+                hard: ARCADE_HARD,
+                oni: ARCADE_HARD,
+                edit: ARCADE_HARD,
+            },
+            score_mode: Some(ScoreMode::ShinUchi),
+            ..Self::tjaplayer3()
+        }
+    }
+
+    /// The rules of AC15: the arcade windows, with the scoring chosen by the chart.
+    pub fn ac15() -> Self {
+        Self {
+            score_mode: None,
+            ..Self::nijiiro()
+        }
+    }
+
+    /// The rules of TJAPlayer3 and this engine before rulesets: the same windows on every difficulty.
+    pub fn tjaplayer3() -> Self {
+        Self {
+            windows: PerDifficulty::default(),
+            gauge: PerDifficulty::default(),
+            score_mode: None,
+            roll_hit_cap: None,
+        }
+    }
+
+    /// Returns the preset with the name, case insensitive: `nijiiro`, `ac15` or `tjaplayer3`.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "nijiiro" => Some(Self::nijiiro()),
+            "ac15" => Some(Self::ac15()),
+            "tjaplayer3" => Some(Self::tjaplayer3()),
+            _ => None,
+        }
+    }
+
+    /// Loads a ruleset from TOML, and [validates](Ruleset::validate) it.
+    ///
+    /// ```
+    /// use taiko_core::ruleset::Ruleset;
+    ///
+    /// let toml = "roll_hit_cap = 20.0\n[windows.oni]\ngreat = 0.02";
+    /// let ruleset = Ruleset::from_toml(toml).unwrap();
+    /// assert_eq!(ruleset.roll_hit_cap, Some(20.0));
+    /// assert_eq!(ruleset.windows.oni.great, 0.02);
+    /// assert_eq!(ruleset.windows.oni.ok, Ruleset::default().windows.oni.ok);
+    /// ```
+    #[cfg(feature = "ruleset-files")]
+    pub fn from_toml(toml: &str) -> Result<Self, String> {
+        let ruleset: Self = toml::from_str(toml).map_err(|error| error.to_string())?;
+        ruleset.validate()?;
+        Ok(ruleset)
+    }
+
+    /// Loads a ruleset from JSON, and [validates](Ruleset::validate) it.
+    #[cfg(feature = "ruleset-files")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let ruleset: Self = serde_json::from_str(json).map_err(|error| error.to_string())?;
+        ruleset.validate()?;
+        Ok(ruleset)
+    }
+
+    /// Checks that the windows are ordered `0 < great <= ok <= miss`, that the gauge rates are finite,
+    /// non-negative and can be passed, and that the `roll_hit_cap` is positive.
+    pub fn validate(&self) -> Result<(), String> {
+        for (difficulty, ranges) in self.windows.iter() {
+            if !(0.0 < ranges.great
+                && ranges.great <= ranges.ok
+                && ranges.ok <= ranges.miss
+                && ranges.miss.is_finite())
+            {
+                return Err(format!(
+                    "windows of {difficulty} are not ordered 0 < great <= ok <= miss"
+                ));
+            }
+        }
+        for (difficulty, levels) in self.gauge.iter() {
+            for gauge in levels {
+                let rule = gauge.rule;
+                if !(rule.miss_factor >= 0.0 && rule.miss_factor.is_finite()) {
+                    return Err(format!(
+                        "miss_factor of {difficulty} level {} is not finite and non-negative",
+                        gauge.level
+                    ));
+                }
+                if !(0.0 < rule.pass_threshold
+                    && rule.pass_threshold <= rule.full_threshold
+                    && rule.full_threshold.is_finite())
+                {
+                    return Err(format!(
+                        "gauge of {difficulty} level {} is not ordered 0 < pass_threshold <= full_threshold",
+                        gauge.level
+                    ));
+                }
+            }
+        }
+        if self
+            .roll_hit_cap
+            .is_some_and(|cap| cap.is_nan() || cap <= 0.0)
+        {
+            return Err("roll_hit_cap is not positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// The judgement windows of the difficulty. Unknown difficulties use the ones of Edit.
+    pub fn judge_windows(&self, difficulty: u8) -> JudgeWindows<Judgement> {
+        self.windows.get(difficulty).windows()
+    }
+
+    /// The gauge rates of the difficulty and level, from [`Ruleset::gauge`] or else the gauge tables.
+    /// Unknown difficulties (e.g. Dan and Tower) use the ones of Edit, and levels the difficulty does not have
+    /// use the closest one it has, so `LEVEL:0` gets the lowest level and `LEVEL:11` and above the highest.
+    pub fn gauge(&self, difficulty: u8, level: u8) -> GaugeRule {
+        let index = (difficulty as usize).min(GUAGE_FULL_THRESHOLD.len() - 1);
+        let table = (0..GUAGE_FULL_THRESHOLD[index].len()).map(|l| {
+            let rule = GaugeRule {
+                miss_factor: GUAGE_MISS_FACTOR[index][l],
+                pass_threshold: GUAGE_PASS_THRESHOLD[index][l],
+                full_threshold: GUAGE_FULL_THRESHOLD[index][l],
+            };
+            (l as u8, rule)
+        });
+        // the ruleset comes first, so it wins over the tables on the same level
+        let levels = self.gauge.get(difficulty).iter();
+        levels
+            .map(|gauge| (gauge.level, gauge.rule))
+            .chain(table)
+            .filter(|(_, rule)| rule.pass_threshold > 0.0 && rule.full_threshold > 0.0)
+            .min_by_key(|(l, _)| l.abs_diff(level))
+            .map(|(_, rule)| rule)
+            .expect("the gauge tables have levels for every difficulty")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for name in ["Nijiiro", "ac15", "tjaplayer3"] {
+            let ruleset = Ruleset::preset(name).unwrap();
+            assert_eq!(ruleset.validate(), Ok(()));
+        }
+        assert!(Ruleset::preset("unknown").is_none());
+
+        let nijiiro = Ruleset::nijiiro();
+        assert_eq!(nijiiro.judge_windows(0).early(), 0.125);
+        assert_eq!(nijiiro.judge_windows(3).early(), 0.1083);
+        assert_eq!(nijiiro.judge_windows(7).early(), 0.1083);
+    }
+
+    #[test]
+    #[cfg(feature = "ruleset-files")]
+    fn test_files() {
+        for name in ["Nijiiro", "ac15", "tjaplayer3"] {
+            let ruleset = Ruleset::preset(name).unwrap();
+            let toml = toml::to_string(&ruleset).unwrap();
+            assert_eq!(Ruleset::from_toml(&toml), Ok(ruleset.clone()));
+            let json = serde_json::to_string(&ruleset).unwrap();
+            assert_eq!(Ruleset::from_json(&json), Ok(ruleset));
+        }
+
+        // only the values that change
+        let ruleset = Ruleset::from_json(
+            r#"{"windows": {"hard": {"miss": 0.2}}, "gauge": {"oni": [{"level": 10, "miss_factor": 1.0, "pass_threshold": 7000.0, "full_threshold": 10000.0}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(ruleset.windows.hard.miss, 0.2);
+        assert_eq!(ruleset.windows.hard.great, RANGE_GREAT);
+        assert_eq!(ruleset.windows.oni, JudgeRanges::default());
+        assert_eq!(ruleset.gauge(3, 10).pass(), 0.7);
+        assert_eq!(ruleset.gauge(3, 9), Ruleset::default().gauge(3, 9));
+        let toml = "[[gauge.oni]]\nlevel = 10\nmiss_factor = 1.0\npass_threshold = 7000.0\nfull_threshold = 10000.0\n\n[windows.hard]\nmiss = 0.2\n";
+        assert_eq!(Ruleset::from_toml(toml), Ok(ruleset));
+
+        // the message of the parser
+        let error = Ruleset::from_json(r#"{"windows": "wide"}"#).unwrap_err();
+        assert!(error.contains("expected struct PerDifficulty"), "{error}");
+        let error = Ruleset::from_toml("roll_hit_cap = \"fast\"").unwrap_err();
+        assert!(error.contains("roll_hit_cap"), "{error}");
+
+        // and of the validation
+        let error = Ruleset::from_toml("[windows.oni]\ngreat = 0.2").unwrap_err();
+        assert_eq!(
+            error,
+            "windows of oni are not ordered 0 < great <= ok <= miss"
+        );
+        let error = Ruleset::from_toml(
+            "[[gauge.oni]]\nlevel = 10\nmiss_factor = inf\npass_threshold = 7000.0\nfull_threshold = 10000.0\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "miss_factor of oni level 10 is not finite and non-negative"
+        );
+        let error = Ruleset::from_json(r#"{"roll_hit_cap": 0.0}"#).unwrap_err();
+        assert_eq!(error, "roll_hit_cap is not positive");
+    }
+
+    #[test]
+    fn test_validate() {
+        let windows = |great, ok, miss| Ruleset {
+            windows: PerDifficulty {
+                normal: JudgeRanges { great, ok, miss },
+                ..PerDifficulty::default()
+            },
+            ..Ruleset::default()
+        };
+        assert_eq!(windows(0.02, 0.02, 0.02).validate(), Ok(()));
+        for (great, ok, miss) in [
+            (0.0, 0.1, 0.2),
+            (-0.1, 0.1, 0.2),
+            (0.1, 0.05, 0.2),
+            (0.1, 0.2, 0.15),
+            (0.1, 0.2, f64::INFINITY),
+            (f64::NAN, 0.1, 0.2),
+        ] {
+            assert!(windows(great, ok, miss).validate().is_err());
+        }
+
+        let gauge = |miss_factor, pass_threshold, full_threshold| Ruleset {
+            gauge: PerDifficulty {
+                edit: vec![LevelGauge {
+                    level: 9,
+                    rule: GaugeRule {
+                        miss_factor,
+                        pass_threshold,
+                        full_threshold,
+                    },
+                }],
+                ..PerDifficulty::default()
+            },
+            ..Ruleset::default()
+        };
+        assert_eq!(gauge(2.0, 8000.0, 10000.0).validate(), Ok(()));
+        assert_eq!(gauge(0.0, 8000.0, 10000.0).validate(), Ok(()));
+        for (miss_factor, pass_threshold, full_threshold) in [
+            (2.0, 0.0, 10000.0),
+            (2.0, 8000.0, 6000.0),
+            (2.0, 8000.0, f64::INFINITY),
+            (2.0, f64::NAN, 10000.0),
+            (-1.0, 8000.0, 10000.0),
+            (f64::INFINITY, 8000.0, 10000.0),
+            (f64::NAN, 8000.0, 10000.0),
+        ] {
+            assert!(gauge(miss_factor, pass_threshold, full_threshold)
+                .validate()
+                .is_err());
+        }
+
+        for cap in [0.0, -1.0, f64::NAN] {
+            let ruleset = Ruleset {
+                roll_hit_cap: Some(cap),
+                ..Ruleset::default()
+            };
+            assert_eq!(
+                ruleset.validate(),
+                Err("roll_hit_cap is not positive".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_gauge() {
+        let ruleset = Ruleset::default();
+        let at = |difficulty: usize, level: usize| GaugeRule {
+            miss_factor: GUAGE_MISS_FACTOR[difficulty][level],
+            pass_threshold: GUAGE_PASS_THRESHOLD[difficulty][level],
+            full_threshold: GUAGE_FULL_THRESHOLD[difficulty][level],
+        };
+        assert_eq!(ruleset.gauge(3, 8), at(3, 8));
+        assert_eq!(ruleset.gauge(3, 0), at(3, 1));
+        assert_eq!(ruleset.gauge(3, 12), at(3, 10));
+        assert_eq!(ruleset.gauge(0, 7), at(0, 5));
+        assert_eq!(ruleset.gauge(6, 10), at(4, 10));
+        assert_eq!(ruleset.gauge(255, 255), at(4, 10));
+
+        // a level of the ruleset, beyond the tables
+        let rule = GaugeRule {
+            miss_factor: 3.0,
+            pass_threshold: 9000.0,
+            full_threshold: 10000.0,
+        };
+        let custom = Ruleset {
+            gauge: PerDifficulty {
+                easy: vec![LevelGauge { level: 8, rule }],
+                ..PerDifficulty::default()
+            },
+            ..Ruleset::default()
+        };
+        assert_eq!(custom.gauge(0, 8), rule);
+        assert_eq!(custom.gauge(0, 12), rule);
+        assert_eq!(custom.gauge(0, 6), at(0, 5));
+        assert_eq!(custom.gauge(1, 8), at(1, 7));
+
+        for difficulty in 0..=u8::MAX {
+            for level in 0..=u8::MAX {
+                let pass = ruleset.gauge(difficulty, level).pass();
+                assert!(pass > 0.0 && pass < 1.0);
+            }
+        }
+    }
+}
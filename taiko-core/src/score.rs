@@ -0,0 +1,171 @@
+//! The scoring systems selected by `SCOREMODE:`.
+//! ref: https://wikiwiki.jp/taiko-fumen/%E3%82%B7%E3%82%B9%E3%83%86%E3%83%A0/%E9%85%8D%E7%82%B9
+
+use serde::{Deserialize, Serialize};
+use tja::TaikoNoteType;
+
+use crate::constant::{
+    BALLOON_HIT_SCORE, BALLOON_POP_SCORE, COMBO_BONUS, COMBO_BONUS_INTERVAL, ROLL_HIT_SCORE,
+    SHIN_UCHI_HIT_SCORE,
+};
+use crate::Judgement;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum ScoreMode {
+    /// `SCOREMODE:0`, the scoring of AC1 to AC7.
+    /// Every 10 combo adds `SCOREDIFF` to the score of a note, up to 100 combo.
+    Ac7,
+    /// `SCOREMODE:1`, the scoring of AC8 to AC14.
+    /// `SCOREDIFF` is added 1, 2, 4 and 8 times from 10, 30, 50 and 100 combo.
+    Ac14,
+    /// `SCOREMODE:2`, the shin-uchi scoring of AC15.
+    /// Every note is worth `SCOREINIT`, without combo, big note or gogo time bonuses.
+    ShinUchi,
+}
+
+impl TryFrom<i32> for ScoreMode {
+    type Error = &'static str;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ScoreMode::Ac7),
+            1 => Ok(ScoreMode::Ac14),
+            2 => Ok(ScoreMode::ShinUchi),
+            _ => Err("unknown score mode"),
+        }
+    }
+}
+
+/// Rounds down to a multiple of 10, as the arcade does for every bonus.
+fn floor10(score: u32) -> u32 {
+    score / 10 * 10
+}
+
+/// Applies the gogo time bonus of 1.2 times.
+fn gogo(score: u32) -> u32 {
+    floor10(score * 12 / 10)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Scoring {
+    pub mode: ScoreMode,
+    /// `SCOREINIT:`, the score of a note without bonuses.
+    pub init: u32,
+    /// `SCOREDIFF:`, the increment of the score of a note by combo.
+    pub diff: u32,
+}
+
+impl Scoring {
+    /// The score of hitting a don or kat with `judgement`, where `combo` includes the note.
+    pub fn note(&self, judgement: Judgement, combo: u32, in_gogo: bool) -> u32 {
+        let steps = match self.mode {
+            ScoreMode::Ac7 => (combo / 10).min(10),
+            ScoreMode::Ac14 => match combo {
+                0..=9 => 0,
+                10..=29 => 1,
+                30..=49 => 2,
+                50..=99 => 4,
+                _ => 8,
+            },
+            ScoreMode::ShinUchi => 0,
+        };
+        let score = self.init + self.diff * steps;
+        let score = match judgement {
+            Judgement::Great => score,
+            Judgement::Ok if self.mode == ScoreMode::ShinUchi => score / 2,
+            Judgement::Ok => floor10(score / 2),
+            _ => return 0,
+        };
+        if in_gogo && self.mode != ScoreMode::ShinUchi {
+            gogo(score)
+        } else {
+            score
+        }
+    }
+
+    /// The score of the second hit on a big note, which doubles the score of the note before shin-uchi.
+    pub fn big_bonus(&self, judgement: Judgement, combo: u32, in_gogo: bool) -> u32 {
+        if self.mode == ScoreMode::ShinUchi {
+            0
+        } else {
+            self.note(judgement, combo, in_gogo)
+        }
+    }
+
+    /// The bonus for reaching `combo`, given every 100 combo before shin-uchi.
+    pub fn combo_bonus(&self, combo: u32) -> u32 {
+        if self.mode != ScoreMode::ShinUchi
+            && combo > 0
+            && combo.is_multiple_of(COMBO_BONUS_INTERVAL)
+        {
+            COMBO_BONUS
+        } else {
+            0
+        }
+    }
+
+    /// The score of a hit on a drumroll or balloon, with the bonus if the hit pops the balloon.
+    pub fn roll(&self, note_type: TaikoNoteType, in_gogo: bool, popped: bool) -> u32 {
+        if self.mode == ScoreMode::ShinUchi {
+            return SHIN_UCHI_HIT_SCORE;
+        }
+
+        let score = match note_type {
+            TaikoNoteType::SmallCombo => ROLL_HIT_SCORE,
+            TaikoNoteType::BigCombo => ROLL_HIT_SCORE * 2,
+            _ if popped => BALLOON_HIT_SCORE + BALLOON_POP_SCORE,
+            _ => BALLOON_HIT_SCORE,
+        };
+        if in_gogo {
+            gogo(score)
+        } else {
+            score
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TaikoNoteType::{Balloon, BigCombo};
+
+    #[test]
+    fn test_scoring() {
+        let ac14 = Scoring {
+            mode: ScoreMode::Ac14,
+            init: 1000,
+            diff: 250,
+        };
+        assert_eq!(ac14.note(Judgement::Great, 9, false), 1000);
+        assert_eq!(ac14.note(Judgement::Great, 30, false), 1500);
+        assert_eq!(ac14.note(Judgement::Great, 100, false), 3000);
+        assert_eq!(ac14.note(Judgement::Ok, 10, false), 620);
+        assert_eq!(ac14.note(Judgement::Great, 10, true), 1500);
+        assert_eq!(ac14.big_bonus(Judgement::Great, 10, true), 1500);
+        assert_eq!(ac14.note(Judgement::Miss, 10, false), 0);
+        assert_eq!(ac14.combo_bonus(100), COMBO_BONUS);
+        assert_eq!(ac14.combo_bonus(150), 0);
+        assert_eq!(ac14.roll(BigCombo, true, false), 720);
+        assert_eq!(ac14.roll(Balloon, false, true), 5300);
+
+        let ac7 = Scoring {
+            mode: ScoreMode::Ac7,
+            ..ac14
+        };
+        assert_eq!(ac7.note(Judgement::Great, 30, false), 1750);
+        assert_eq!(ac7.note(Judgement::Great, 500, false), 3500);
+
+        let shin_uchi = Scoring {
+            mode: ScoreMode::ShinUchi,
+            ..ac14
+        };
+        assert_eq!(shin_uchi.note(Judgement::Great, 100, true), 1000);
+        assert_eq!(shin_uchi.big_bonus(Judgement::Great, 100, true), 0);
+        assert_eq!(shin_uchi.note(Judgement::Ok, 100, false), 500);
+        assert_eq!(shin_uchi.combo_bonus(100), 0);
+        assert_eq!(shin_uchi.roll(Balloon, true, true), SHIN_UCHI_HIT_SCORE);
+
+        assert_eq!(ScoreMode::try_from(1), Ok(ScoreMode::Ac14));
+        assert!(ScoreMode::try_from(3).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+//! Sticking (which hand hits which note) suggestions for taiko courses.
+
+use serde::{Deserialize, Serialize};
+use tja::{TJACourse, TaikoNoteType, TaikoNoteVariant};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Hand {
+    Left,
+    Right,
+    /// Big notes are hit with both hands.
+    Both,
+}
+
+impl Hand {
+    pub fn other(self) -> Hand {
+        match self {
+            Hand::Left => Hand::Right,
+            Hand::Right => Hand::Left,
+            Hand::Both => Hand::Both,
+        }
+    }
+}
+
+/// The hand that starts groups of notes, which can not be both hands.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum StrongHand {
+    Left,
+    Right,
+}
+
+impl From<StrongHand> for Hand {
+    fn from(value: StrongHand) -> Self {
+        match value {
+            StrongHand::Left => Hand::Left,
+            StrongHand::Right => Hand::Right,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum StickingStyle {
+    /// Every note is hit with the other hand than the previous one, only restarting after rests.
+    FullAlternate,
+    /// Every group of notes starts with the strong hand, and alternates within the group.
+    DominantStart,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct StickingOptions {
+    pub style: StickingStyle,
+    pub strong_hand: StrongHand,
+    /// Gaps of at least this length (in seconds) are rests, after which the strong hand starts again.
+    pub rest: f64,
+}
+
+impl Default for StickingOptions {
+    fn default() -> Self {
+        Self {
+            style: StickingStyle::DominantStart,
+            strong_hand: StrongHand::Right,
+            rest: 1.0,
+        }
+    }
+}
+
+/// Suggests a hand for every note of the course, in the order of `course.notes`.
+/// Notes that are not dons or kats (drumrolls, balloons and events) get `None`,
+/// and the strong hand starts again after them.
+pub fn sticking(course: &TJACourse, options: &StickingOptions) -> Vec<Option<Hand>> {
+    let strong_hand = Hand::from(options.strong_hand);
+    let notes = &course.notes;
+    let mut order = (0..notes.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| notes[*a].start.total_cmp(&notes[*b].start));
+
+    let mut hands = vec![None; notes.len()];
+    // the previous don or kat with its hand, and the interval before it
+    let mut previous: Option<(f64, Hand, Option<f64>)> = None;
+    for i in order {
+        let note = &notes[i];
+        if note.variant != TaikoNoteVariant::Don && note.variant != TaikoNoteVariant::Kat {
+            if note.variant != TaikoNoteVariant::Invisible {
+                previous = None;
+            }
+            continue;
+        }
+        if note.note_type == TaikoNoteType::Big {
+            hands[i] = Some(Hand::Both);
+            previous = Some((note.start, Hand::Both, None));
+            continue;
+        }
+
+        let hand = match previous {
+            Some((time, hand, interval)) if hand != Hand::Both => {
+                let gap = note.start - time;
+                // a gap longer than the notes before it starts a new group
+                let new_group = options.style == StickingStyle::DominantStart
+                    && interval.is_some_and(|interval| gap > interval * 1.5);
+                if gap >= options.rest || new_group {
+                    strong_hand
+                } else {
+                    hand.other()
+                }
+            }
+            _ => strong_hand,
+        };
+        let interval = previous.map(|(time, _, _)| note.start - time);
+        hands[i] = Some(hand);
+        previous = Some((note.start, hand, interval));
+    }
+    hands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tja::TaikoNote;
+
+    fn course(notes: &[(f64, TaikoNoteVariant, TaikoNoteType)]) -> TJACourse {
+        let mut course = TJACourse::new(3);
+        course.notes = notes
+            .iter()
+            .map(|&(start, variant, note_type)| TaikoNote {
+                start,
+                duration: 0.0,
+                volume: 1,
+                variant,
+                note_type,
+                speed: 120.0,
+            })
+            .collect();
+        course
+    }
+
+    #[test]
+    fn test_sticking() {
+        use Hand::{Both as B, Left as L, Right as R};
+        use TaikoNoteType::{BarLine, Big, Small};
+        use TaikoNoteVariant::{Don, Invisible, Kat};
+
+        let course = course(&[
+            (0.0, Don, Small),
+            (0.25, Kat, Small),
+            (0.5, Don, Small),
+            (0.5, Invisible, BarLine),
+            // a group after a longer gap
+            (1.0, Don, Small),
+            (1.125, Don, Small),
+            (1.25, Kat, Small),
+            (1.5, Don, Big),
+            (1.75, Kat, Small),
+            // a rest
+            (3.0, Kat, Small),
+        ]);
+
+        let hands = sticking(&course, &StickingOptions::default());
+        assert_eq!(
+            hands,
+            vec![
+                Some(R),
+                Some(L),
+                Some(R),
+                None,
+                Some(R),
+                Some(L),
+                Some(R),
+                Some(B),
+                Some(R),
+                Some(R),
+            ]
+        );
+
+        let options = StickingOptions {
+            style: StickingStyle::FullAlternate,
+            strong_hand: StrongHand::Left,
+            ..Default::default()
+        };
+        let hands = sticking(&course, &options);
+        assert_eq!(
+            hands,
+            vec![
+                Some(L),
+                Some(R),
+                Some(L),
+                None,
+                Some(R),
+                Some(L),
+                Some(R),
+                Some(B),
+                Some(L),
+                Some(L),
+            ]
+        );
+    }
+}
@@ -1,16 +1,41 @@
-use rhythm_core::{Note, Rhythm};
-use serde::{Deserialize, Serialize};
-use tja::{TaikoNote, TaikoNoteVariant};
+use rhythm_core::{Judge, JudgeWindows, Note, Rhythm, RhythmState};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tja::{TJACourse, TaikoNote, TaikoNoteType, TaikoNoteVariant};
 
-use crate::constant::{
-    GUAGE_FULL_THRESHOLD, GUAGE_MISS_FACTOR, GUAGE_PASS_THRESHOLD, RANGE_GREAT, RANGE_MISS,
-    RANGE_OK,
-};
+use crate::constant::BIG_HIT_WINDOW;
+use crate::ruleset::{GaugeRule, JudgeRanges, Ruleset};
+use crate::score::{ScoreMode, Scoring};
+use crate::sticking::Hand;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 pub enum Hit {
+    /// A don on an unknown side.
     Don,
+    /// A kat on an unknown side.
     Kat,
+    DonLeft,
+    DonRight,
+    KatLeft,
+    KatRight,
+}
+
+impl Hit {
+    /// The note variant the hit matches.
+    pub fn variant(self) -> TaikoNoteVariant {
+        match self {
+            Hit::Don | Hit::DonLeft | Hit::DonRight => TaikoNoteVariant::Don,
+            Hit::Kat | Hit::KatLeft | Hit::KatRight => TaikoNoteVariant::Kat,
+        }
+    }
+
+    /// The hand of the hit, or `None` for [`Hit::Don`] and [`Hit::Kat`].
+    pub fn hand(self) -> Option<Hand> {
+        match self {
+            Hit::DonLeft | Hit::KatLeft => Some(Hand::Left),
+            Hit::DonRight | Hit::KatRight => Some(Hand::Right),
+            Hit::Don | Hit::Kat => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
@@ -20,6 +45,19 @@ pub enum Judgement {
     Miss,
     ComboHit,
     Nothing,
+    /// The second hit on a big note judged as [`Judgement::Great`].
+    GreatBig,
+    /// The second hit on a big note judged as [`Judgement::Ok`].
+    OkBig,
+    /// The hit that popped a balloon or kusudama.
+    BalloonPopped,
+}
+
+impl Judgement {
+    /// The default judgement windows of the Don and Kat notes.
+    pub fn windows() -> JudgeWindows<Judgement> {
+        JudgeRanges::default().windows()
+    }
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -41,7 +79,7 @@ impl Ord for CalculatedNote {
 
 impl PartialOrd for CalculatedNote {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.inner.start.partial_cmp(&other.inner.start)
+        Some(Ord::cmp(self, other))
     }
 }
 
@@ -127,15 +165,47 @@ pub struct GameSource {
     pub level: u8,
     pub scoreinit: Option<i32>,
     pub scorediff: Option<i32>,
+    /// The `SCOREMODE:` of the course. Without it (or with an unknown mode) the AC8-AC14 scoring is used,
+    /// the default of the TJA format. Use a [`Ruleset::score_mode`] to play every chart with shin-uchi scoring.
+    #[serde(default)]
+    pub scoremode: Option<i32>,
     pub notes: Vec<TaikoNote>,
 }
 
+impl GameSource {
+    /// The `COURSE:` of the course, clamped to the range of a difficulty.
+    pub fn difficulty_of(course: &TJACourse) -> u8 {
+        course.course.clamp(0, u8::MAX as i32) as u8
+    }
+
+    /// The `LEVEL:` of the course, clamped to the range of a level. A missing level is 0.
+    pub fn level_of(course: &TJACourse) -> u8 {
+        course.level.unwrap_or(0).clamp(0, u8::MAX as i32) as u8
+    }
+}
+
+impl From<&TJACourse> for GameSource {
+    /// The notes are taken as they are, move them by the `OFFSET:` of the TJA to play on the time of the music.
+    fn from(course: &TJACourse) -> Self {
+        Self {
+            difficulty: Self::difficulty_of(course),
+            level: Self::level_of(course),
+            scoreinit: course.scoreinit,
+            scorediff: course.scorediff,
+            scoremode: course.scoremode,
+            notes: course.notes.clone(),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
 pub struct InputState<H> {
-    /// The current time played in the music, in seconds.
+    /// The current time played in the music, in seconds. A non-finite time does not move the engine.
     pub time: f64,
-    /// Hit event that happened since the last frame.
-    pub hit: Option<H>,
+    /// Hit events that happened since the last frame, as `(time, hit)` pairs in seconds.
+    /// They are judged in time order, each at its own time (clamped between the last frame and `time`).
+    /// Hits at non-finite times are ignored.
+    pub hits: Vec<(f64, H)>,
 }
 
 #[derive(Clone, PartialEq, PartialOrd, Debug, Serialize, Deserialize)]
@@ -151,8 +221,11 @@ pub struct OutputState {
     /// The current soul gauge of the player.
     pub gauge: f64,
 
-    /// The judgement of the hit in the last frame.
+    /// The judgement of the last hit in the frame.
     pub judgement: Option<Judgement>,
+    /// The judgements of all hits in the frame, in time order.
+    #[serde(default)]
+    pub judgements: Vec<Judgement>,
 
     /// Display state
     pub display: Vec<CalculatedNote>,
@@ -167,6 +240,9 @@ pub struct Final {
     pub goods: u32,
     pub misses: u32,
     pub max_hit: u32,
+    /// The balloons that ended before they were popped. Kusudamas are not failed when they end unpopped.
+    #[serde(default)]
+    pub unpopped: u32,
     pub passed: bool,
 }
 
@@ -176,12 +252,57 @@ pub trait TaikoEngine<H> {
     fn finalize(&self) -> Final;
 }
 
+/// A big note that was hit once, waiting for the second hit on the same colour with the other hand.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PendingBig {
+    pub variant: TaikoNoteVariant,
+    /// The hand of the first hit, if known.
+    pub hand: Option<Hand>,
+    /// The time of the big note, in seconds.
+    #[serde(default)]
+    pub time: f64,
+    /// The last time the second hit counts, in seconds.
+    pub deadline: f64,
+    /// The judgement of the first hit.
+    pub judgement: Judgement,
+    /// The score of the second hit.
+    pub bonus: u32,
+}
+
+/// The playback state of a [`DefaultTaikoEngine`], taken by [`DefaultTaikoEngine::snapshot`].
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct EngineState {
+    pub rhythm: RhythmState<CalculatedNote>,
+    pub score: u32,
+    pub current_combo: u32,
+    pub max_combo: u32,
+    pub gauge: f64,
+    pub current_time: f64,
+    pub passed_display: Vec<CalculatedNote>,
+    pub judgements: Vec<Judgement>,
+    pub max_hit_count: u32,
+    #[serde(default)]
+    pub pending_big: Option<PendingBig>,
+    #[serde(default)]
+    pub unpopped: u32,
+    #[serde(default)]
+    pub last_roll_hit: Option<f64>,
+}
+
+/// A deserialized engine is checked against its notes, so a corrupted save fails to load instead of panicking later.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct DefaultTaikoEngine {
     rhythm: Rhythm<CalculatedNote>,
 
     difficulty: u8,
     level: u8,
-    scoreinit: i32,
+    scoring: Scoring,
+    /// Whether each note of the source is in gogo time.
+    gogo: Vec<bool>,
+    ruleset: Ruleset,
+    windows: JudgeWindows<Judgement>,
+    gauge_rule: GaugeRule,
 
     score: u32,
     current_combo: u32,
@@ -191,15 +312,232 @@ pub struct DefaultTaikoEngine {
     current_time: f64,
 
     total_notes: usize,
+    /// The longest time a note is visible before it starts.
+    lookahead: f64,
 
     passed_display: Vec<CalculatedNote>,
 
     judgements: Vec<Judgement>,
     max_hit_count: u32,
+    pending_big: Option<PendingBig>,
+    unpopped: u32,
+    /// The time of the last counted hit on a drumroll or balloon.
+    last_roll_hit: Option<f64>,
 }
 
-impl TaikoEngine<Hit> for DefaultTaikoEngine {
-    fn new(src: GameSource) -> Self {
+impl Serialize for DefaultTaikoEngine {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DefaultTaikoEngine {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let engine = Self::deserialize(deserializer)?;
+        if engine
+            .rhythm
+            .notes
+            .iter()
+            .any(|note| note.idx >= engine.gogo.len())
+        {
+            return Err(serde::de::Error::custom("invalid engine"));
+        }
+        Ok(engine)
+    }
+}
+
+impl DefaultTaikoEngine {
+    /// Takes a snapshot of the playback state, e.g. for save states or rollback.
+    pub fn snapshot(&self) -> EngineState {
+        EngineState {
+            rhythm: self.rhythm.snapshot(),
+            score: self.score,
+            current_combo: self.current_combo,
+            max_combo: self.max_combo,
+            gauge: self.gauge,
+            current_time: self.current_time,
+            passed_display: self.passed_display.clone(),
+            judgements: self.judgements.clone(),
+            max_hit_count: self.max_hit_count,
+            pending_big: self.pending_big,
+            unpopped: self.unpopped,
+            last_roll_hit: self.last_roll_hit,
+        }
+    }
+
+    /// Restores a playback state taken by [`DefaultTaikoEngine::snapshot`] on an engine with the same source.
+    pub fn restore(&mut self, state: EngineState) -> Result<(), &'static str> {
+        self.rhythm.restore(state.rhythm)?;
+        self.score = state.score;
+        self.current_combo = state.current_combo;
+        self.max_combo = state.max_combo;
+        self.gauge = state.gauge;
+        self.current_time = state.current_time;
+        self.passed_display = state.passed_display;
+        self.judgements = state.judgements;
+        self.max_hit_count = state.max_hit_count;
+        self.pending_big = state.pending_big;
+        self.unpopped = state.unpopped;
+        self.last_roll_hit = state.last_roll_hit;
+        Ok(())
+    }
+
+    /// Moves the engine to `time`, and judges the notes that passed as missed.
+    fn advance(&mut self, time: f64) {
+        let passed = self.rhythm.forward(time - self.current_time);
+        self.current_time = time;
+
+        if self.pending_big.is_some_and(|big| time > big.deadline) {
+            self.pending_big = None;
+        }
+
+        for note in passed.iter() {
+            if note.variant() == TaikoNoteVariant::Don || note.variant() == TaikoNoteVariant::Kat {
+                self.current_combo = 0;
+                self.gauge -= (1.0 / self.total_notes as f64) * self.gauge_rule.miss_factor;
+                self.judgements.push(Judgement::Miss);
+            } else if note.inner.note_type == TaikoNoteType::Balloon {
+                self.unpopped += 1;
+            }
+        }
+        self.gauge = self.gauge.clamp(0.0, 1.0);
+
+        self.passed_display.extend(passed);
+    }
+
+    /// The distance from the current time to the next note the hit would judge, if any.
+    fn next_hittable(&self, variant: TaikoNoteVariant) -> Option<f64> {
+        let time = self.current_time;
+        let early = self.windows.early();
+        self.rhythm
+            .availables_until(time)
+            .find(|note| {
+                note.volume() > 0
+                    && note.start() <= time
+                    && time <= note.start() + note.duration()
+                    && note.matches_variant(variant)
+            })
+            .map(|note| {
+                if note.variant() == TaikoNoteVariant::Both {
+                    0.0
+                } else {
+                    (time - (note.start() + early)).abs()
+                }
+            })
+    }
+
+    /// Judges a hit at the current time.
+    fn judge(&mut self, hit: Hit) -> Judgement {
+        let variant = hit.variant();
+        if let Some(big) = self.pending_big.filter(|big| big.variant == variant) {
+            // hits without a side can not be told apart from the first hit,
+            // so they only count when they are closer to the big note than to the next note
+            let second = match (big.hand, hit.hand()) {
+                (Some(first), Some(second)) => first != second,
+                _ => self
+                    .next_hittable(variant)
+                    .is_none_or(|next| self.current_time - big.time < next),
+            };
+            if second {
+                self.pending_big = None;
+                self.score += big.bonus;
+                let judgement = if big.judgement == Judgement::Great {
+                    Judgement::GreatBig
+                } else {
+                    Judgement::OkBig
+                };
+                self.judgements.push(judgement);
+                return judgement;
+            }
+        }
+
+        // the judgement, with the type of the hit note, whether it is in gogo time and whether it popped
+        let mut note_time = self.current_time;
+        let (judgement, note_type, in_gogo, popped) = match self.rhythm.hit(variant) {
+            Some((note, _)) if note.variant() == TaikoNoteVariant::Both => {
+                let too_fast = self.ruleset.roll_hit_cap.is_some_and(|cap| {
+                    self.last_roll_hit
+                        .is_some_and(|last| self.current_time - last < 1.0 / cap)
+                });
+                if too_fast {
+                    note.set_volume(note.volume() + 1);
+                    self.judgements.push(Judgement::Nothing);
+                    return Judgement::Nothing;
+                }
+                self.last_roll_hit = Some(self.current_time);
+                note.hit_count += 1;
+                self.max_hit_count = self.max_hit_count.max(note.hit_count);
+                let popped = note.volume() == 0;
+                let judgement = if popped {
+                    Judgement::BalloonPopped
+                } else {
+                    Judgement::ComboHit
+                };
+                (judgement, note.inner.note_type, self.gogo[note.idx], popped)
+            }
+            Some((note, delta_from_start)) => {
+                let note_type = note.inner.note_type;
+                let in_gogo = self.gogo[note.idx];
+                let offset = delta_from_start - self.windows.early();
+                note_time -= offset;
+                let judgement = self.windows.judge(offset).unwrap_or(Judgement::Miss);
+                (judgement, note_type, in_gogo, false)
+            }
+            None => (Judgement::Nothing, TaikoNoteType::Small, false, false),
+        };
+
+        let full = self.gauge_rule.full_threshold;
+        match judgement {
+            Judgement::Great | Judgement::Ok => {
+                self.current_combo += 1;
+                self.max_combo = self.max_combo.max(self.current_combo);
+
+                self.score += self.scoring.note(judgement, self.current_combo, in_gogo);
+                self.score += self.scoring.combo_bonus(self.current_combo);
+
+                if note_type == TaikoNoteType::Big {
+                    self.pending_big = Some(PendingBig {
+                        variant,
+                        hand: hit.hand(),
+                        time: note_time,
+                        deadline: self.current_time + BIG_HIT_WINDOW,
+                        judgement,
+                        bonus: self
+                            .scoring
+                            .big_bonus(judgement, self.current_combo, in_gogo),
+                    });
+                }
+
+                self.gauge += if judgement == Judgement::Great {
+                    1.0 / self.total_notes as f64 / full
+                } else {
+                    (1.0 / self.total_notes as f64)
+                        * (if self.difficulty >= 3 { 0.5 } else { 0.75 })
+                        / full
+                };
+            }
+            Judgement::Miss => {
+                self.current_combo = 0;
+
+                self.gauge -= (1.0 / self.total_notes as f64) * self.gauge_rule.miss_factor / full;
+            }
+            Judgement::ComboHit | Judgement::BalloonPopped => {
+                self.score += self.scoring.roll(note_type, in_gogo, popped);
+            }
+            _ => {}
+        };
+        self.gauge = self.gauge.clamp(0.0, 1.0);
+
+        self.judgements.push(judgement);
+        judgement
+    }
+}
+
+impl DefaultTaikoEngine {
+    /// Creates an engine that plays by the ruleset instead of the default one.
+    pub fn with_ruleset(src: GameSource, ruleset: Ruleset) -> Self {
+        let windows = ruleset.judge_windows(src.difficulty);
+        let gauge_rule = ruleset.gauge(src.difficulty, src.level);
         let notes = src
             .notes
             .iter()
@@ -219,8 +557,8 @@ impl TaikoEngine<Hit> for DefaultTaikoEngine {
                 let inner = match note.variant {
                     TaikoNoteVariant::Don | TaikoNoteVariant::Kat => {
                         let mut note = *note;
-                        note.start -= RANGE_MISS;
-                        note.duration = RANGE_MISS * 2.0;
+                        note.start -= windows.early();
+                        note.duration = windows.early() + windows.late();
                         note
                     }
                     _ => *note,
@@ -242,141 +580,102 @@ impl TaikoEngine<Hit> for DefaultTaikoEngine {
             })
             .count()
             .max(1);
-        let rhythm = Rhythm::new(notes.clone());
+        let lookahead = notes
+            .iter()
+            .filter(|note| note.visible_end > note.visible_start)
+            .map(|note| note.start() - note.visible_start)
+            .fold(0.0, f64::max);
+        let rhythm = Rhythm::new(notes);
         let scoreinit = if let Some(s) = src.scoreinit {
             s
         } else {
-            let s = (1_000_000 + total_notes - 1) / total_notes;
-            let s = (s + 9) / 10 * 10;
+            let s = 1_000_000usize.div_ceil(total_notes);
+            let s = s.div_ceil(10) * 10;
             s as i32
         };
+        let scoring = Scoring {
+            mode: ruleset.score_mode.unwrap_or_else(|| {
+                src.scoremode
+                    .and_then(|mode| ScoreMode::try_from(mode).ok())
+                    .unwrap_or(ScoreMode::Ac14)
+            }),
+            init: scoreinit.max(0) as u32,
+            diff: src.scorediff.unwrap_or(0).max(0) as u32,
+        };
+
+        let mut order = (0..src.notes.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| src.notes[*a].start.total_cmp(&src.notes[*b].start));
+        let mut gogo = vec![false; src.notes.len()];
+        let mut in_gogo = false;
+        for idx in order {
+            match src.notes[idx].note_type {
+                TaikoNoteType::GogoStart => in_gogo = true,
+                TaikoNoteType::GogoEnd => in_gogo = false,
+                _ => {}
+            }
+            gogo[idx] = in_gogo;
+        }
 
         DefaultTaikoEngine {
             rhythm,
             difficulty: src.difficulty,
             level: src.level,
-            scoreinit,
+            scoring,
+            gogo,
+            ruleset,
+            windows,
+            gauge_rule,
             score: 0,
             current_combo: 0,
             max_combo: 0,
             gauge: 0.0,
             current_time: 0.0,
             total_notes,
+            lookahead,
             passed_display: vec![],
             judgements: vec![],
             max_hit_count: 0,
+            pending_big: None,
+            unpopped: 0,
+            last_roll_hit: None,
         }
     }
 
-    fn forward(&mut self, input: InputState<Hit>) -> OutputState {
-        let time_diff = input.time - self.current_time;
-        self.current_time = input.time;
-        let passed = self.rhythm.forward(time_diff);
-
-        let judgement = if let Some(hit) = input.hit {
-            match hit {
-                Hit::Don => {
-                    if let Some((note, delta_from_start)) = self.rhythm.hit(TaikoNoteVariant::Don) {
-                        if note.variant() == TaikoNoteVariant::Both {
-                            note.hit_count += 1;
-                            self.max_hit_count = self.max_hit_count.max(note.hit_count);
-                            Some(Judgement::ComboHit)
-                        } else {
-                            let delta = (delta_from_start - note.duration() / 2.0).abs();
-                            if delta < RANGE_GREAT {
-                                Some(Judgement::Great)
-                            } else if delta < RANGE_OK {
-                                Some(Judgement::Ok)
-                            } else {
-                                Some(Judgement::Miss)
-                            }
-                        }
-                    } else {
-                        Some(Judgement::Nothing)
-                    }
-                }
-                Hit::Kat => {
-                    if let Some((note, t)) = self.rhythm.hit(TaikoNoteVariant::Kat) {
-                        if note.variant() == TaikoNoteVariant::Both {
-                            note.hit_count += 1;
-                            self.max_hit_count = self.max_hit_count.max(note.hit_count);
-                            Some(Judgement::ComboHit)
-                        } else {
-                            let delta = (t - note.duration() / 2.0).abs();
-                            if delta < RANGE_GREAT {
-                                Some(Judgement::Great)
-                            } else if delta < RANGE_OK {
-                                Some(Judgement::Ok)
-                            } else {
-                                Some(Judgement::Miss)
-                            }
-                        }
-                    } else {
-                        Some(Judgement::Nothing)
-                    }
-                }
-            }
-        } else {
-            None
-        };
-
-        // missed notes
-        for note in passed.iter() {
-            if note.variant() == TaikoNoteVariant::Don || note.variant() == TaikoNoteVariant::Kat {
-                self.current_combo = 0;
-                self.gauge -= (1.0 / self.total_notes as f64)
-                    * GUAGE_MISS_FACTOR[self.difficulty as usize][self.level as usize];
-                self.judgements.push(Judgement::Miss);
-            }
-        }
-
-        let full = GUAGE_FULL_THRESHOLD[self.difficulty as usize][self.level as usize];
-        match judgement {
-            Some(Judgement::Great) => {
-                self.score += self.scoreinit as u32;
-
-                self.current_combo += 1;
-                self.max_combo = self.max_combo.max(self.current_combo);
-
-                self.gauge += 1.0 / self.total_notes as f64 / full;
-            }
-            Some(Judgement::Ok) => {
-                self.score += (self.scoreinit as u32) / 2;
-
-                self.current_combo += 1;
-                self.max_combo = self.max_combo.max(self.current_combo);
+    /// The ruleset the engine plays by.
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+}
 
-                self.gauge += (1.0 / self.total_notes as f64)
-                    * (if self.difficulty >= 3 { 0.5 } else { 0.75 })
-                    / full;
-            }
-            Some(Judgement::Miss) => {
-                self.current_combo = 0;
+impl TaikoEngine<Hit> for DefaultTaikoEngine {
+    fn new(src: GameSource) -> Self {
+        Self::with_ruleset(src, Ruleset::default())
+    }
 
-                self.gauge -= (1.0 / self.total_notes as f64)
-                    * GUAGE_MISS_FACTOR[self.difficulty as usize][self.level as usize]
-                    / full;
-            }
-            Some(Judgement::ComboHit) => {
-                self.score += 100;
-            }
-            _ => {}
+    fn forward(&mut self, input: InputState<Hit>) -> OutputState {
+        // a NaN would stick to the current time of the engine
+        let now = if input.time.is_finite() {
+            input.time
+        } else {
+            self.current_time
         };
+        let mut hits = input.hits;
+        hits.retain(|(time, _)| time.is_finite());
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
 
-        if let Some(judgement) = judgement {
-            self.judgements.push(judgement);
+        let mut judgements = vec![];
+        for (time, hit) in hits {
+            self.advance(time.clamp(self.current_time, now.max(self.current_time)));
+            judgements.push(self.judge(hit));
         }
+        self.advance(now);
 
-        self.gauge = self.gauge.max(0.0).min(1.0);
-
-        self.passed_display.extend(passed);
-        self.passed_display.retain(|note| note.visible(input.time));
+        self.passed_display.retain(|note| note.visible(now));
 
         let available_display = self
             .rhythm
-            .availables()
-            .iter()
-            .filter(|note| note.visible(input.time))
+            .availables_until(now + self.lookahead)
+            .filter(|note| note.visible(now))
             .cloned()
             .collect::<Vec<_>>();
 
@@ -389,7 +688,8 @@ impl TaikoEngine<Hit> for DefaultTaikoEngine {
             current_combo: self.current_combo,
             max_combo: self.max_combo,
             gauge: self.gauge,
-            judgement,
+            judgement: judgements.last().copied(),
+            judgements,
             display,
         }
     }
@@ -408,9 +708,7 @@ impl TaikoEngine<Hit> for DefaultTaikoEngine {
             }
         }
 
-        let passed = self.gauge
-            >= (GUAGE_PASS_THRESHOLD[self.difficulty as usize][self.level as usize]
-                / GUAGE_FULL_THRESHOLD[self.difficulty as usize][self.level as usize]);
+        let passed = self.gauge >= self.gauge_rule.pass();
 
         Final {
             score: self.score,
@@ -420,7 +718,428 @@ impl TaikoEngine<Hit> for DefaultTaikoEngine {
             goods,
             misses,
             max_hit: self.max_hit_count,
+            unpopped: self.unpopped,
             passed,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tja::TaikoNoteType;
+
+    fn source() -> GameSource {
+        let note = |start: f64, variant| TaikoNote {
+            start,
+            duration: 0.0,
+            volume: 1,
+            variant,
+            note_type: TaikoNoteType::Small,
+            speed: 120.0,
+        };
+
+        GameSource {
+            difficulty: 3,
+            level: 8,
+            scoreinit: None,
+            scorediff: None,
+            scoremode: None,
+            notes: vec![
+                note(1.0, TaikoNoteVariant::Don),
+                note(1.5, TaikoNoteVariant::Kat),
+                note(2.0, TaikoNoteVariant::Don),
+                note(2.5, TaikoNoteVariant::Kat),
+            ],
+        }
+    }
+
+    fn play(engine: &mut DefaultTaikoEngine, time: f64, hit: Option<Hit>) -> OutputState {
+        engine.forward(InputState {
+            time,
+            hits: hit.map(|hit| vec![(time, hit)]).unwrap_or_default(),
+        })
+    }
+
+    #[test]
+    fn test_judgement() {
+        let mut engine = DefaultTaikoEngine::new(source());
+        let judge = |output: OutputState| output.judgement.unwrap();
+
+        assert_eq!(
+            judge(play(&mut engine, 0.8, Some(Hit::Don))),
+            Judgement::Nothing
+        );
+        assert_eq!(
+            judge(play(&mut engine, 0.98, Some(Hit::Don))),
+            Judgement::Great
+        );
+        assert_eq!(
+            judge(play(&mut engine, 1.55, Some(Hit::Kat))),
+            Judgement::Ok
+        );
+        assert_eq!(
+            judge(play(&mut engine, 2.09, Some(Hit::Don))),
+            Judgement::Miss
+        );
+        assert_eq!(
+            judge(play(&mut engine, 2.5, Some(Hit::Don))),
+            Judgement::Nothing
+        );
+
+        let result = engine.finalize();
+        assert_eq!((result.greats, result.goods, result.misses), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_timed_hits() {
+        let mut engine = DefaultTaikoEngine::new(source());
+        // hits out of order, a hit after the frame and a hit at no time
+        let output = engine.forward(InputState {
+            time: 1.6,
+            hits: vec![
+                (1.5, Hit::Kat),
+                (0.98, Hit::Don),
+                (1.0, Hit::Don),
+                (1.7, Hit::Don),
+                (f64::NAN, Hit::Kat),
+            ],
+        });
+        assert_eq!(
+            output.judgements,
+            vec![
+                Judgement::Great,
+                Judgement::Nothing,
+                Judgement::Great,
+                Judgement::Nothing
+            ]
+        );
+        assert_eq!(output.judgement, Some(Judgement::Nothing));
+        assert_eq!(output.current_combo, 2);
+
+        let output = engine.forward(InputState {
+            time: f64::NAN,
+            hits: vec![],
+        });
+        assert_eq!(output.judgements, vec![]);
+        assert_eq!(engine.snapshot().current_time, 1.6);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut engine = DefaultTaikoEngine::new(source());
+        play(&mut engine, 1.0, Some(Hit::Don));
+        play(&mut engine, 1.51, Some(Hit::Kat));
+
+        let state = engine.snapshot();
+        let saved = engine.clone();
+
+        play(&mut engine, 2.0, Some(Hit::Kat));
+        play(&mut engine, 3.0, None);
+        assert_ne!(engine.finalize(), saved.finalize());
+
+        engine.restore(state).unwrap();
+        assert_eq!(engine, saved);
+
+        let output = play(&mut engine, 2.0, Some(Hit::Don));
+        assert_eq!(output.judgement, Some(Judgement::Great));
+        assert_eq!(output.current_combo, 3);
+    }
+
+    #[test]
+    fn test_scoremode() {
+        // without SCOREMODE:, the AC8-AC14 scoring
+        let mut src = source();
+        src.scoreinit = Some(1000);
+        src.scorediff = Some(100);
+        src.notes[2].note_type = TaikoNoteType::Big;
+        src.notes.push(TaikoNote {
+            start: 1.75,
+            duration: 0.0,
+            volume: 1,
+            variant: TaikoNoteVariant::Invisible,
+            note_type: TaikoNoteType::GogoStart,
+            speed: 120.0,
+        });
+
+        let mut engine = DefaultTaikoEngine::new(src.clone());
+        for (time, hit) in [(1.0, Hit::Don), (1.5, Hit::Kat), (2.0, Hit::Don)] {
+            play(&mut engine, time, Some(hit));
+        }
+        assert_eq!(play(&mut engine, 2.0, None).score, 1000 + 1000 + 1200);
+        let output = play(&mut engine, 2.02, Some(Hit::Don));
+        assert_eq!(output.judgement, Some(Judgement::GreatBig));
+        let output = play(&mut engine, 2.5, Some(Hit::Kat));
+        assert_eq!(output.score, 1000 + 1000 + 2400 + 1200);
+        assert_eq!(output.current_combo, 4);
+
+        // the second hit is with the same hand
+        let mut engine = DefaultTaikoEngine::new(src.clone());
+        play(&mut engine, 2.0, Some(Hit::DonLeft));
+        let output = play(&mut engine, 2.02, Some(Hit::DonLeft));
+        assert_eq!(output.judgement, Some(Judgement::Nothing));
+        let mut engine = DefaultTaikoEngine::new(src.clone());
+        play(&mut engine, 2.0, Some(Hit::DonLeft));
+        let output = play(&mut engine, 2.02, Some(Hit::DonRight));
+        assert_eq!(output.judgement, Some(Judgement::GreatBig));
+
+        // the second hit is too late
+        let mut engine = DefaultTaikoEngine::new(src.clone());
+        play(&mut engine, 2.0, Some(Hit::Don));
+        let output = play(&mut engine, 2.1, Some(Hit::Don));
+        assert_eq!(output.judgement, Some(Judgement::Nothing));
+        assert_eq!(output.score, 1200);
+
+        // shin-uchi without bonuses
+        src.scoremode = Some(2);
+        let mut engine = DefaultTaikoEngine::new(src.clone());
+        for (time, hit) in [(1.0, Hit::Don), (1.5, Hit::Kat), (2.0, Hit::Don)] {
+            play(&mut engine, time, Some(hit));
+        }
+        assert_eq!(play(&mut engine, 2.5, Some(Hit::Kat)).score, 4000);
+
+        // or shin-uchi for every chart, chosen by the ruleset
+        src.scoremode = Some(1);
+        let ruleset = Ruleset {
+            score_mode: Some(ScoreMode::ShinUchi),
+            ..Ruleset::default()
+        };
+        let mut engine = DefaultTaikoEngine::with_ruleset(src, ruleset);
+        for (time, hit) in [(1.0, Hit::Don), (1.5, Hit::Kat), (2.0, Hit::Don)] {
+            play(&mut engine, time, Some(hit));
+        }
+        assert_eq!(play(&mut engine, 2.5, Some(Hit::Kat)).score, 4000);
+    }
+
+    #[test]
+    fn test_big_in_stream() {
+        let note = |start: f64, variant, note_type| TaikoNote {
+            start,
+            duration: 0.0,
+            volume: 1,
+            variant,
+            note_type,
+            speed: 120.0,
+        };
+        let mut src = source();
+        src.notes = vec![
+            note(1.0, TaikoNoteVariant::Don, TaikoNoteType::Big),
+            note(1.05, TaikoNoteVariant::Don, TaikoNoteType::Small),
+            note(2.0, TaikoNoteVariant::Don, TaikoNoteType::Big),
+            note(2.02, TaikoNoteVariant::Kat, TaikoNoteType::Small),
+        ];
+        let judge = |engine: &mut DefaultTaikoEngine, time, hit| {
+            play(engine, time, Some(hit)).judgement.unwrap()
+        };
+
+        // a hit closer to the next note goes to it
+        let mut engine = DefaultTaikoEngine::new(src.clone());
+        assert_eq!(judge(&mut engine, 1.0, Hit::Don), Judgement::Great);
+        assert_eq!(judge(&mut engine, 1.04, Hit::Don), Judgement::Great);
+        // a kat does not drop the big note
+        assert_eq!(judge(&mut engine, 2.0, Hit::Don), Judgement::Great);
+        assert_eq!(judge(&mut engine, 2.02, Hit::Kat), Judgement::Great);
+        assert_eq!(judge(&mut engine, 2.03, Hit::Don), Judgement::GreatBig);
+        assert_eq!(engine.finalize().greats, 4);
+
+        // a hit closer to the big note is its second hit
+        let mut engine = DefaultTaikoEngine::new(src);
+        assert_eq!(judge(&mut engine, 1.0, Hit::Don), Judgement::Great);
+        assert_eq!(judge(&mut engine, 1.01, Hit::Don), Judgement::GreatBig);
+        assert_eq!(judge(&mut engine, 1.05, Hit::Don), Judgement::Great);
+    }
+
+    #[test]
+    fn test_balloon() {
+        let balloon = |start: f64, volume: u16, note_type| TaikoNote {
+            start,
+            duration: 1.0,
+            volume,
+            variant: TaikoNoteVariant::Both,
+            note_type,
+            speed: 120.0,
+        };
+        let mut src = source();
+        src.notes = vec![
+            balloon(1.0, 3, TaikoNoteType::Balloon),
+            balloon(3.0, 5, TaikoNoteType::Yam),
+            balloon(5.0, 5, TaikoNoteType::Balloon),
+        ];
+        src.scoremode = Some(2);
+
+        let mut engine = DefaultTaikoEngine::new(src);
+        let judge = |output: OutputState| output.judgement.unwrap();
+        assert_eq!(
+            judge(play(&mut engine, 1.1, Some(Hit::Kat))),
+            Judgement::Nothing
+        );
+        assert_eq!(
+            judge(play(&mut engine, 1.2, Some(Hit::Don))),
+            Judgement::ComboHit
+        );
+        assert_eq!(
+            judge(play(&mut engine, 1.3, Some(Hit::Don))),
+            Judgement::ComboHit
+        );
+        assert_eq!(
+            judge(play(&mut engine, 1.4, Some(Hit::Don))),
+            Judgement::BalloonPopped
+        );
+        assert_eq!(
+            judge(play(&mut engine, 1.5, Some(Hit::Don))),
+            Judgement::Nothing
+        );
+
+        // kusudamas take kats too
+        assert_eq!(
+            judge(play(&mut engine, 3.1, Some(Hit::Kat))),
+            Judgement::ComboHit
+        );
+        assert_eq!(
+            judge(play(&mut engine, 3.2, Some(Hit::Don))),
+            Judgement::ComboHit
+        );
+        play(&mut engine, 4.5, None);
+
+        // an unpopped balloon is failed, an unpopped kusudama is not
+        assert_eq!(engine.finalize().unpopped, 0);
+        assert_eq!(
+            judge(play(&mut engine, 5.1, Some(Hit::Don))),
+            Judgement::ComboHit
+        );
+        play(&mut engine, 7.0, None);
+
+        let result = engine.finalize();
+        assert_eq!(result.unpopped, 1);
+        assert_eq!(result.score, 600);
+    }
+
+    #[test]
+    fn test_ruleset() {
+        // narrower windows on oni
+        let mut engine = DefaultTaikoEngine::with_ruleset(source(), Ruleset::nijiiro());
+        let judge = |output: OutputState| output.judgement.unwrap();
+        assert_eq!(
+            judge(play(&mut engine, 0.972, Some(Hit::Don))),
+            Judgement::Ok
+        );
+        let mut engine = DefaultTaikoEngine::new(source());
+        assert_eq!(
+            judge(play(&mut engine, 0.972, Some(Hit::Don))),
+            Judgement::Great
+        );
+
+        let mut src = source();
+        src.notes = vec![TaikoNote {
+            start: 1.0,
+            duration: 1.0,
+            volume: 10,
+            variant: TaikoNoteVariant::Both,
+            note_type: TaikoNoteType::Balloon,
+            speed: 120.0,
+        }];
+        let ruleset = Ruleset {
+            roll_hit_cap: Some(10.0),
+            ..Ruleset::default()
+        };
+        let mut engine = DefaultTaikoEngine::with_ruleset(src, ruleset);
+        let output = engine.forward(InputState {
+            time: 1.5,
+            hits: vec![(1.1, Hit::Don), (1.15, Hit::Don), (1.25, Hit::Don)],
+        });
+        assert_eq!(
+            output.judgements,
+            vec![Judgement::ComboHit, Judgement::Nothing, Judgement::ComboHit]
+        );
+        assert_eq!(engine.snapshot().last_roll_hit, Some(1.25));
+    }
+
+    #[test]
+    fn test_samples() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../tja/samples");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let raw = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            let tja = tja::TJAParser::new().parse(raw).unwrap();
+            for course in tja.courses {
+                let source = GameSource::from(&course);
+                let hits = course
+                    .notes
+                    .iter()
+                    .filter_map(|note| match note.variant {
+                        TaikoNoteVariant::Don => Some((note.start, Hit::Don)),
+                        TaikoNoteVariant::Kat => Some((note.start, Hit::Kat)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let end = course
+                    .notes
+                    .iter()
+                    .map(|note| note.start + note.duration)
+                    .fold(0.0, f64::max)
+                    + 1.0;
+
+                // the course as it is, and with levels and difficulties the gauge tables do not have
+                let variants = [
+                    (source.difficulty, source.level),
+                    (source.difficulty, 0),
+                    (source.difficulty, 11),
+                    (5, source.level),
+                    (6, 12),
+                ];
+                for (difficulty, level) in variants {
+                    let src = GameSource {
+                        difficulty,
+                        level,
+                        ..source.clone()
+                    };
+
+                    let mut engine = DefaultTaikoEngine::new(src.clone());
+                    engine.forward(InputState {
+                        time: end,
+                        hits: hits.clone(),
+                    });
+                    let result = engine.finalize();
+                    assert_eq!(result.greats as usize, hits.len());
+                    assert_eq!(result.gauge, 1.0);
+                    assert!(result.passed);
+
+                    let mut engine = DefaultTaikoEngine::new(src);
+                    engine.forward(InputState {
+                        time: end,
+                        hits: vec![],
+                    });
+                    let result = engine.finalize();
+                    assert_eq!(result.gauge, 0.0);
+                    assert!(!result.passed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut engine = DefaultTaikoEngine::new(source());
+        play(&mut engine, 1.0, Some(Hit::Don));
+        play(&mut engine, 1.51, Some(Hit::Kat));
+
+        let json = serde_json::to_string(&engine).unwrap();
+        let mut restored: DefaultTaikoEngine = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, engine);
+
+        for time in [2.0, 2.5, 3.0] {
+            assert_eq!(
+                play(&mut restored, time, Some(Hit::Don)),
+                play(&mut engine, time, Some(Hit::Don))
+            );
+        }
+        assert_eq!(restored.finalize(), engine.finalize());
+
+        // a corrupted save fails to load
+        let mut value = serde_json::to_value(&engine).unwrap();
+        let mut corrupted = value.clone();
+        corrupted["gogo"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<DefaultTaikoEngine>(corrupted).is_err());
+        value["rhythm"]["cursor"] = serde_json::json!(100);
+        assert!(serde_json::from_value::<DefaultTaikoEngine>(value).is_err());
+    }
+}
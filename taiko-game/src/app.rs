@@ -181,6 +181,7 @@ impl App {
                 max_combo: 0,
                 gauge: 0.0,
                 judgement: None,
+                judgements: vec![],
                 display: vec![],
             },
             enter_countdown: 0,
@@ -230,7 +231,7 @@ impl App {
                     tui::Event::Tick => action_tx.send(Action::Tick)?,
                     tui::Event::Render => action_tx.send(Action::Render)?,
                     tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
-                    tui::Event::Key(key) => match self.page {
+                    tui::Event::Key(key, _) => match self.page {
                         Page::SongMenu => {
                             self.songmenu
                                 .handle(&mut self.state, e, action_tx.clone())?;
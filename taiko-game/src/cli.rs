@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use clap::Parser;
+use taiko_core::ruleset::Ruleset;
 
 use crate::utils::version;
 
@@ -73,4 +74,26 @@ pub struct AppArgs {
         default_value_t = false
     )]
     pub eco: bool,
+
+    #[arg(
+        long,
+        value_name = "RULESET",
+        help = "The ruleset of the game: nijiiro, ac15, tjaplayer3, or the path to a TOML or JSON ruleset file",
+        value_parser = parse_ruleset,
+        default_value = "tjaplayer3"
+    )]
+    pub ruleset: Ruleset,
+}
+
+fn parse_ruleset(value: &str) -> Result<Ruleset, String> {
+    if let Some(ruleset) = Ruleset::preset(value) {
+        return Ok(ruleset);
+    }
+
+    let path = PathBuf::from(value);
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => Ruleset::from_json(&content),
+        _ => Ruleset::from_toml(&content),
+    }
 }
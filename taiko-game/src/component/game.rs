@@ -6,7 +6,7 @@ use crate::{
     tui::{Event, Frame},
 };
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use kira::{sound::static_sound::StaticSoundSettings, tween::Tween};
 use ratatui::{
     prelude::*,
@@ -15,9 +15,9 @@ use ratatui::{
         *,
     },
 };
-use rhythm_core::Note;
+use rhythm_core::{transform, Note};
 use taiko_core::{
-    constant::{COURSE_TYPE, GUAGE_FULL_THRESHOLD, GUAGE_PASS_THRESHOLD, RANGE_GREAT, RANGE_OK},
+    constant::{COURSE_TYPE, RANGE_GREAT, RANGE_OK},
     DefaultTaikoEngine, Final, GameSource, Hit, InputState, Judgement, TaikoEngine,
 };
 use tja::{TaikoNote, TaikoNoteType, TaikoNoteVariant};
@@ -28,7 +28,8 @@ use super::Component;
 pub struct GameScreen {
     last_hit: i32,
     last_hit_show: i32,
-    hit: Option<Hit>,
+    /// The hits since the last tick, with the song time they happened at.
+    hits: Vec<(f64, Hit)>,
     guage_color_change: i32,
     last_hit_type: Option<Hit>,
     hit_show: i32,
@@ -43,7 +44,7 @@ impl Component for GameScreen {
         Self {
             last_hit: 0,
             last_hit_show: 0,
-            hit: None,
+            hits: vec![],
             guage_color_change: 0,
             last_hit_type: None,
             hit_show: 0,
@@ -63,8 +64,11 @@ impl Component for GameScreen {
         let guage_chunk = vertical_chunks[0];
         let game_zone = vertical_chunks[1];
 
-        let difficulty = app.selected_course.as_ref().unwrap().course as usize;
-        let level = app.selected_course.as_ref().unwrap().level.unwrap_or(0) as usize;
+        let course = app.selected_course.as_ref().unwrap();
+        let (difficulty, level) = (
+            GameSource::difficulty_of(course),
+            GameSource::level_of(course),
+        );
         let guage_color = if app.output.gauge == 1.0 {
             self.guage_color_change += 1;
             if self.guage_color_change >= 20 {
@@ -79,9 +83,7 @@ impl Component for GameScreen {
             } else {
                 Color::White
             }
-        } else if app.output.gauge
-            >= (GUAGE_PASS_THRESHOLD[difficulty][level] / GUAGE_FULL_THRESHOLD[difficulty][level])
-        {
+        } else if app.output.gauge >= app.args.ruleset.gauge(difficulty, level).pass() {
             Color::Yellow
         } else {
             Color::White
@@ -180,9 +182,9 @@ impl Component for GameScreen {
 
         let hit_reflection_color = if self.last_hit_type.is_some() && self.hit_show > 0 {
             self.hit_show -= 1;
-            match self.last_hit_type.as_ref().unwrap() {
-                Hit::Don => Color::Red,
-                Hit::Kat => Color::Cyan,
+            match self.last_hit_type.unwrap().variant() {
+                TaikoNoteVariant::Don => Color::Red,
+                _ => Color::Cyan,
             }
         } else {
             Color::White
@@ -213,7 +215,7 @@ impl Component for GameScreen {
         tx: UnboundedSender<Action>,
     ) -> Result<()> {
         match event {
-            Event::Key(e) => match e {
+            Event::Key(e, received) => match e {
                 KeyEvent {
                     code: KeyCode::Char('c'),
                     modifiers: KeyModifiers::CONTROL,
@@ -222,25 +224,39 @@ impl Component for GameScreen {
                 | KeyEvent {
                     code: KeyCode::Esc, ..
                 } => tx.send(Action::Switch(Page::CourseMenu))?,
+                // auto-repeat is not a hit
                 KeyEvent {
                     code: KeyCode::Char(c),
+                    kind: KeyEventKind::Press,
                     ..
-                } => match c {
-                    ' ' | 'f' | 'g' | 'h' | 'j' | 'c' | 'v' | 'b' | 'n' | 'm' => {
-                        app.player.play(app.sounds["don"].clone())?;
-                        self.hit.replace(Hit::Don);
-                        self.last_hit_type.replace(Hit::Don);
-                        self.hit_show = app.args.tps as i32 / 40;
-                    }
-                    'd' | 's' | 'a' | 't' | 'r' | 'e' | 'w' | 'q' | 'x' | 'z' | 'k' | 'l' | ';'
-                    | '\'' | 'y' | 'u' | 'i' | 'o' | 'p' | ',' | '.' | '/' => {
-                        app.player.play(app.sounds["kat"].clone())?;
-                        self.hit.replace(Hit::Kat);
-                        self.last_hit_type.replace(Hit::Kat);
+                } => {
+                    // the left and right halves of the keyboard are the left and right sides of the drum
+                    let hit = match c {
+                        ' ' => Some(Hit::Don),
+                        'f' | 'g' | 'c' | 'v' | 'b' => Some(Hit::DonLeft),
+                        'h' | 'j' | 'n' | 'm' => Some(Hit::DonRight),
+                        'd' | 's' | 'a' | 't' | 'r' | 'e' | 'w' | 'q' | 'x' | 'z' => {
+                            Some(Hit::KatLeft)
+                        }
+                        'k' | 'l' | ';' | '\'' | 'y' | 'u' | 'i' | 'o' | 'p' | ',' | '.' | '/' => {
+                            Some(Hit::KatRight)
+                        }
+                        _ => None,
+                    };
+                    if let Some(hit) = hit {
+                        let sound = if hit.variant() == TaikoNoteVariant::Don {
+                            "don"
+                        } else {
+                            "kat"
+                        };
+                        app.player.play(app.sounds[sound].clone())?;
+                        // the song time when the key was received, rather than when it is handled
+                        let time = app.player_time() - received.elapsed().as_secs_f64();
+                        self.hits.push((time, hit));
+                        self.last_hit_type.replace(hit);
                         self.hit_show = app.args.tps as i32 / 40;
                     }
-                    _ => {}
-                },
+                }
                 _ => {}
             },
             Event::Tick => {
@@ -278,7 +294,7 @@ impl Component for GameScreen {
                                 && (player_time - note.start) < 0.05
                             {
                                 app.player.play(app.sounds["don"].clone())?;
-                                self.hit.replace(Hit::Don);
+                                self.hits.push((player_time, Hit::Don));
                                 self.last_hit_type.replace(Hit::Don);
                                 self.hit_show = app.args.tps as i32 / 40;
                                 self.auto_play.as_mut().unwrap().remove(0);
@@ -290,7 +306,7 @@ impl Component for GameScreen {
                                 && (player_time - note.start) < 0.05
                             {
                                 app.player.play(app.sounds["kat"].clone())?;
-                                self.hit.replace(Hit::Kat);
+                                self.hits.push((player_time, Hit::Kat));
                                 self.last_hit_type.replace(Hit::Kat);
                                 self.hit_show = app.args.tps as i32 / 40;
                                 self.auto_play.as_mut().unwrap().remove(0);
@@ -301,7 +317,7 @@ impl Component for GameScreen {
                             if player_time > note.start {
                                 if self.auto_play_combo_sleep == 0 {
                                     app.player.play(app.sounds["don"].clone())?;
-                                    self.hit.replace(Hit::Don);
+                                    self.hits.push((player_time, Hit::Don));
                                     self.last_hit_type.replace(Hit::Don);
                                     self.hit_show = app.args.tps as i32 / 40;
                                     self.auto_play_combo_sleep = app.args.tps / 20;
@@ -320,14 +336,14 @@ impl Component for GameScreen {
 
                 let input: InputState<Hit> = InputState {
                     time: player_time,
-                    hit: self.hit.take(),
+                    hits: std::mem::take(&mut self.hits),
                 };
 
                 app.output = app.taiko.as_mut().unwrap().forward(input);
                 if app.output.judgement.is_some() {
                     self.last_hit = match app.output.judgement.unwrap() {
-                        Judgement::Great => 1,
-                        Judgement::Ok => 2,
+                        Judgement::Great | Judgement::GreatBig => 1,
+                        Judgement::Ok | Judgement::OkBig => 2,
                         Judgement::Miss => 3,
                         _ => 0,
                     };
@@ -349,25 +365,19 @@ impl Component for GameScreen {
         let mut course = song.tja().courses.get(selected).unwrap().clone();
 
         let offset = song.tja().header.offset.unwrap_or(0.0) as f64;
-        for note in course.notes.iter_mut() {
-            note.start -= offset;
-            note.start += app.args.track_offset;
-        }
+        transform::offset(&mut course.notes, app.args.track_offset - offset);
 
-        let source = GameSource {
-            difficulty: course.course as u8,
-            level: course.level.unwrap_or(0) as u8,
-            scoreinit: course.scoreinit,
-            scorediff: course.scorediff,
-            notes: course.notes.clone(),
-        };
+        let source = GameSource::from(&course);
 
         if app.args.auto {
             self.auto_play.replace(course.notes.clone());
         }
 
         app.selected_course.replace(course);
-        app.taiko.replace(DefaultTaikoEngine::new(source));
+        app.taiko.replace(DefaultTaikoEngine::with_ruleset(
+            source,
+            app.args.ruleset.clone(),
+        ));
 
         if let Some(mut playing) = app.playing.take() {
             playing.stop(Tween::default())?;
@@ -495,7 +505,7 @@ impl Component for GameResult {
         event: Event,
         tx: UnboundedSender<Action>,
     ) -> Result<()> {
-        if let Event::Key(e) = event {
+        if let Event::Key(e, _) = event {
             match e {
                 KeyEvent {
                     code: KeyCode::Char('c'),
@@ -7,7 +7,7 @@ use crate::{
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
-use taiko_core::constant::COURSE_TYPE;
+use taiko_core::constant::course_name;
 use tokio::sync::mpsc::UnboundedSender;
 
 use super::Component;
@@ -65,7 +65,7 @@ impl Component for SongMenu {
         event: Event,
         tx: UnboundedSender<Action>,
     ) -> Result<()> {
-        if let Event::Key(e) = event {
+        if let Event::Key(e, _) = event {
             match e {
                 KeyEvent {
                     code: KeyCode::Char('c'),
@@ -155,16 +155,11 @@ impl Component for CourseMenu {
     fn render(&mut self, app: &mut AppGlobalState, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         let song = app.selected_song.as_ref().unwrap();
         let names = song.tja().courses.iter().map(|course| {
-            (if course.course < COURSE_TYPE.len() as i32 {
-                format!(
-                    "{:<8} ({})",
-                    COURSE_TYPE[course.course as usize],
-                    course.level.unwrap_or(0)
-                )
-            } else {
-                "Unknown".to_owned()
-            })
-            .to_string()
+            format!(
+                "{:<8} ({})",
+                course_name(course.course),
+                course.level.unwrap_or(0)
+            )
         });
         let list = List::new(names)
             .block(
@@ -192,7 +187,7 @@ impl Component for CourseMenu {
         event: Event,
         tx: UnboundedSender<Action>,
     ) -> Result<()> {
-        if let Event::Key(e) = event {
+        if let Event::Key(e, _) = event {
             match e {
                 KeyEvent {
                     code: KeyCode::Char('c'),
@@ -1,7 +1,7 @@
 use crate::{app::AppGlobalState, tui::Frame};
 use color_eyre::eyre::Result;
 use ratatui::{prelude::*, widgets::*};
-use taiko_core::constant::COURSE_TYPE;
+use taiko_core::constant::course_name;
 
 use super::Component;
 
@@ -50,11 +50,7 @@ impl Component for TopBar {
                     .title
                     .as_ref()
                     .unwrap(),
-                if app.selected_course.as_ref().unwrap().course < COURSE_TYPE.len() as i32 {
-                    COURSE_TYPE[app.selected_course.as_ref().unwrap().course as usize]
-                } else {
-                    "Unknown"
-                },
+                course_name(app.selected_course.as_ref().unwrap().course),
                 app.player_time(),
                 app.output.score,
                 app.output.current_combo,
@@ -1,6 +1,6 @@
 use std::{
     ops::{Deref, DerefMut},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use color_eyre::eyre::Result;
@@ -8,7 +8,8 @@ use crossterm::{
     cursor,
     event::{
         DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-        Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent,
+        Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyboardEnhancementFlags,
+        MouseEvent, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -27,6 +28,9 @@ pub fn io() -> IO {
 }
 pub type Frame<'a> = ratatui::Frame<'a>;
 
+/// Without key event types from the terminal, a press of the same key within this interval is taken as auto-repeat.
+pub const REPEAT_INTERVAL: Duration = Duration::from_millis(70);
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
     Init,
@@ -38,7 +42,8 @@ pub enum Event {
     FocusGained,
     FocusLost,
     Paste(String),
-    Key(KeyEvent),
+    /// A key press, with the instant it was received, so it can be timed independently of the tick rate.
+    Key(KeyEvent, #[serde(skip, default = "Instant::now")] Instant),
     Mouse(MouseEvent),
     Resize(u16, u16),
 }
@@ -53,6 +58,8 @@ pub struct Tui {
     pub tick_rate: f64,
     pub mouse: bool,
     pub paste: bool,
+    /// Whether the terminal reports press, repeat and release (kitty keyboard protocol).
+    pub keyboard_enhancement: bool,
 }
 
 impl Tui {
@@ -65,6 +72,7 @@ impl Tui {
         let task = tokio::spawn(async {});
         let mouse = false;
         let paste = false;
+        let keyboard_enhancement = false;
         Ok(Self {
             terminal,
             task,
@@ -75,6 +83,7 @@ impl Tui {
             tick_rate,
             mouse,
             paste,
+            keyboard_enhancement,
         })
     }
 
@@ -106,6 +115,10 @@ impl Tui {
         let cancellation_token = self.cancellation_token.clone();
         let event_tx = self.event_tx.clone();
         self.task = tokio::spawn(async move {
+            let mut last_press: Option<(KeyCode, Instant)> = None;
+            // whether the terminal reports repeats and releases, which the kitty keyboard protocol may not do
+            // even when it is supported
+            let mut event_types = false;
             let mut reader = crossterm::event::EventStream::new();
             let mut tick_interval = tokio::time::interval(tick_delay);
             let mut render_interval = tokio::time::interval(render_delay);
@@ -122,9 +135,17 @@ impl Tui {
                     match maybe_event {
                       Some(Ok(evt)) => {
                         match evt {
-                          CrosstermEvent::Key(key) => {
-                            if key.kind == KeyEventKind::Press {
-                              event_tx.send(Event::Key(key)).unwrap();
+                          CrosstermEvent::Key(mut key) => {
+                            let now = Instant::now();
+                            event_types |= key.kind != KeyEventKind::Press;
+                            if key.kind == KeyEventKind::Press && !event_types {
+                              if last_press.is_some_and(|(code, at)| code == key.code && now - at < REPEAT_INTERVAL) {
+                                key.kind = KeyEventKind::Repeat;
+                              }
+                              last_press = Some((key.code, now));
+                            }
+                            if key.kind != KeyEventKind::Release {
+                              event_tx.send(Event::Key(key, now)).unwrap();
                             }
                           },
                           CrosstermEvent::Mouse(mouse) => {
@@ -181,6 +202,19 @@ impl Tui {
     pub fn enter(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
         crossterm::execute!(io(), EnterAlternateScreen, cursor::Hide)?;
+        self.keyboard_enhancement =
+            crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if self.keyboard_enhancement {
+            // repeat and release events of plain keys are only reported as escape codes
+            crossterm::execute!(
+                io(),
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                        | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+                )
+            )?;
+        }
         if self.mouse {
             crossterm::execute!(io(), EnableMouseCapture)?;
         }
@@ -201,6 +235,9 @@ impl Tui {
             if self.mouse {
                 crossterm::execute!(io(), DisableMouseCapture)?;
             }
+            if self.keyboard_enhancement {
+                crossterm::execute!(io(), PopKeyboardEnhancementFlags)?;
+            }
             crossterm::execute!(io(), LeaveAlternateScreen, cursor::Show)?;
             crossterm::terminal::disable_raw_mode()?;
         }
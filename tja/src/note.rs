@@ -58,7 +58,11 @@ pub enum TaikoNoteType {
     Big,
     SmallCombo,
     BigCombo,
+    /// A balloon, `7`, which only takes dons.
     Balloon,
+    /// A kusudama, `9`, which takes dons and kats. Unlike a balloon, it is not failed when it ends unpopped.
+    /// It scores like a balloon. The arcade shares its hit count between the players, which is out of the scope of the
+    /// single-player engines.
     Yam,
     GogoStart,
     GogoEnd,
@@ -122,8 +126,11 @@ impl Note for TaikoNote {
             TaikoNoteVariant::Don => {
                 self.variant == TaikoNoteVariant::Don || self.variant == TaikoNoteVariant::Both
             }
+            // balloons only take dons, while kusudamas (yams) take both
             TaikoNoteVariant::Kat => {
-                self.variant == TaikoNoteVariant::Kat || self.variant == TaikoNoteVariant::Both
+                self.variant == TaikoNoteVariant::Kat
+                    || (self.variant == TaikoNoteVariant::Both
+                        && self.note_type != TaikoNoteType::Balloon)
             }
             _ => false,
         }
@@ -140,6 +147,6 @@ impl Ord for TaikoNote {
 
 impl PartialOrd for TaikoNote {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.start.partial_cmp(&other.start)
+        Some(Ord::cmp(self, other))
     }
 }
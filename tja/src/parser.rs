@@ -28,6 +28,8 @@ impl TJAParser {
         let mut measure = (4, 4);
         let mut segments: Vec<(f32, f32, Vec<char>)> = Vec::new();
         let mut current_combo: Option<TaikoNote> = None;
+        // SCOREMODE may be given for the whole song, before the courses
+        let mut scoremode = None;
 
         for mut line in tja_content.as_ref().lines() {
             if let Some(pair) = line.split_once("//") {
@@ -55,8 +57,11 @@ impl TJAParser {
                         "STYLE" => tja.header.style = Some(value.to_string()),
                         "GENRE" => tja.header.genre = Some(value.to_string()),
                         "ARTIST" => tja.header.artist = Some(value.to_string()),
+                        "SCOREMODE" => scoremode = value.parse().ok(),
                         "COURSE" => {
-                            course = Some(TJACourse::new(parse_course(value)));
+                            let mut new = TJACourse::new(parse_course(value));
+                            new.scoremode = scoremode;
+                            course = Some(new);
                             balloons.clear();
                             time_ms = 0.0;
                             bpm = tja.header.bpm.unwrap_or(60.0);
@@ -83,6 +88,7 @@ impl TJAParser {
                     }
                     "SCOREINIT" => course.as_mut().unwrap().scoreinit = value.parse().ok(),
                     "SCOREDIFF" => course.as_mut().unwrap().scorediff = value.parse().ok(),
+                    "SCOREMODE" => course.as_mut().unwrap().scoremode = value.parse().ok(),
                     _ => {}
                 }
             } else if let Some(raw) = line.strip_prefix('#') {
@@ -164,6 +170,11 @@ impl TJAParser {
 
                     let mut first = true;
                     for (bpm, scroll, segment) in segments.iter() {
+                        let bpms = &mut course.as_mut().unwrap().bpms;
+                        if bpms.last().is_none_or(|(_, last)| last != bpm) {
+                            bpms.push((time_ms, *bpm));
+                        }
+
                         let duration = (60.0 / *bpm as f64)
                             * (measure.0 as f64 / measure.1 as f64)
                             * (4.0 / notes as f64);
@@ -315,4 +326,60 @@ mod tests {
         let expected = fs::read_to_string(JSON_FILE).unwrap();
         assert_eq!(tja_json, expected);
     }
+
+    #[test]
+    fn course_stats() {
+        let raw = "TITLE:Test\nBPM:120\nCOURSE:Oni\nLEVEL:8\n#START\n1020,\n#BPMCHANGE 240\n5008,\n#GOGOSTART\n3400,\n#END\n";
+        let tja = TJAParser::new().parse(raw).unwrap();
+        let course = &tja.courses[0];
+
+        assert_eq!(course.note_count(), 5);
+        assert_eq!(course.bpms, vec![(0.0, 120.0), (2.0, 240.0)]);
+        assert_eq!(course.bpm_range(), Some((120.0, 240.0)));
+        assert_eq!(course.drumroll_time(), 0.75);
+        assert_eq!(course.length(), 3.25);
+        assert_eq!(course.peak_density(1.0), Some((3.0, 2)));
+    }
+
+    #[test]
+    fn validate_course() {
+        use rhythm_core::validate::{validate, IssueKind, Lanes};
+
+        // a don inside a drumroll
+        let raw = "TITLE:Test\nBPM:120\nCOURSE:Oni\n#START\n5010,\n8000,\n#END\n";
+        let tja = TJAParser::new().parse(raw).unwrap();
+        let mut notes = tja.courses[0].notes.clone();
+        let kinds = validate(&notes, Lanes::Single)
+            .iter()
+            .map(|d| (d.kind, d.time))
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![(IssueKind::Overlap, 1.0)]);
+
+        // a kat at the time of the don
+        let don = notes
+            .iter()
+            .find(|note| note.variant == TaikoNoteVariant::Don)
+            .copied()
+            .unwrap();
+        notes.push(TaikoNote {
+            variant: TaikoNoteVariant::Kat,
+            ..don
+        });
+        let kinds = validate(&notes, Lanes::Single)
+            .iter()
+            .map(|d| d.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![IssueKind::Overlap, IssueKind::Overlap, IssueKind::Duplicate]
+        );
+    }
+
+    #[test]
+    fn parse_scoremode() {
+        let raw = "BPM:120\nSCOREMODE:1\nCOURSE:Oni\n#START\n1,\n#END\nCOURSE:Hard\nSCOREMODE:2\n#START\n1,\n#END\n";
+        let tja = TJAParser::new().parse(raw).unwrap();
+        assert_eq!(tja.courses[0].scoremode, Some(1));
+        assert_eq!(tja.courses[1].scoremode, Some(2));
+    }
 }
@@ -1,7 +1,9 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::note::TaikoNote;
+use rhythm_core::stats;
+
+use crate::note::{TaikoNote, TaikoNoteType, TaikoNoteVariant};
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg(feature = "serde")]
@@ -72,7 +74,13 @@ pub struct TJACourse {
     pub level: Option<i32>,
     pub scoreinit: Option<i32>,
     pub scorediff: Option<i32>,
+    /// The scoring system: 0 for AC1-AC7, 1 for AC8-AC14 and 2 for AC15 (shin-uchi).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scoremode: Option<i32>,
     pub notes: Vec<TaikoNote>,
+    /// The BPM changes of the course, as `(time, bpm)` pairs.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bpms: Vec<(f64, f32)>,
 }
 
 impl TJACourse {
@@ -82,7 +90,56 @@ impl TJACourse {
             level: None,
             scoreinit: None,
             scorediff: None,
+            scoremode: None,
             notes: Vec::new(),
+            bpms: Vec::new(),
         }
     }
+
+    /// Returns the notes without the invisible events (bar lines and gogo time markers).
+    pub fn visible_notes(&self) -> Vec<TaikoNote> {
+        self.notes
+            .iter()
+            .filter(|note| note.variant != TaikoNoteVariant::Invisible)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the number of notes, drumrolls and balloons included, invisible events excluded.
+    pub fn note_count(&self) -> usize {
+        self.notes
+            .iter()
+            .filter(|note| note.variant != TaikoNoteVariant::Invisible)
+            .count()
+    }
+
+    /// Returns the lowest and the highest BPM of the course.
+    pub fn bpm_range(&self) -> Option<(f32, f32)> {
+        self.bpms.iter().fold(None, |range, (_, bpm)| match range {
+            None => Some((*bpm, *bpm)),
+            Some((min, max)) => Some((bpm.min(min), bpm.max(max))),
+        })
+    }
+
+    /// Returns the total duration of the drumrolls, in seconds.
+    pub fn drumroll_time(&self) -> f64 {
+        self.notes
+            .iter()
+            .filter(|note| {
+                note.note_type == TaikoNoteType::SmallCombo
+                    || note.note_type == TaikoNoteType::BigCombo
+            })
+            .map(|note| note.duration)
+            .sum()
+    }
+
+    /// Returns the time from the first visible note to the end of the last one.
+    pub fn length(&self) -> f64 {
+        stats::length(&self.visible_notes())
+    }
+
+    /// Returns the start time and the note count of the densest `window` seconds of the course.
+    pub fn peak_density(&self, window: f64) -> Option<(f64, usize)> {
+        stats::peak_density(&self.visible_notes(), window)
+    }
 }